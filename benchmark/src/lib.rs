@@ -2,7 +2,7 @@ extern crate hwloc;
 extern crate libc;
 extern crate regex;
 
-use faster_rs::FasterKv;
+use faster_rs::{FasterKv, ReadResult};
 use hwloc::{CpuSet, ObjectType, Topology, CPUBIND_THREAD};
 use regex::Regex;
 use std::fs::File;
@@ -11,9 +11,9 @@ use std::os::unix::prelude::FileExt;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Barrier, Mutex};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
-const K_CHECKPOINT_SECONDS: u64 = 30;
+pub const K_CHECKPOINT_SECONDS: u64 = 30;
 const K_COMPLETE_PENDING_INTERVAL: usize = 1600;
 const K_REFRESH_INTERVAL: usize = 64;
 const K_RUN_TIME: u64 = 360;
@@ -142,7 +142,14 @@ pub fn load_files(load_file: &str, run_file: &str) -> (Vec<u64>, Vec<u64>) {
     (init_keys, run_keys)
 }
 
-pub fn populate_store(store: &Arc<FasterKv>, keys: &Arc<Vec<u64>>, num_threads: u8) {
+/// Populates the store with fixed-size records when `pod` is set, to be used with
+/// a `pod` run of the benchmark
+pub fn populate_store(
+    store: &Arc<FasterKv>,
+    keys: &Arc<Vec<u64>>,
+    num_threads: u8,
+    pod: bool,
+) {
     let topo = Arc::new(Mutex::new(Topology::new()));
     let idx = Arc::new(AtomicUsize::new(0));
     let mut threads = vec![];
@@ -164,22 +171,25 @@ pub fn populate_store(store: &Arc<FasterKv>, keys: &Arc<Vec<u64>>, num_threads:
                     .unwrap();
             }
 
-            let _session = store.start_session();
+            let session = store.start_session();
             let mut chunk_idx = idx.fetch_add(K_CHUNK_SIZE, Ordering::SeqCst);
             while chunk_idx < K_INIT_COUNT {
                 for i in chunk_idx..(chunk_idx + K_CHUNK_SIZE) {
                     if i % K_REFRESH_INTERVAL == 0 {
-                        store.refresh();
+                        session.refresh();
                         if i % K_COMPLETE_PENDING_INTERVAL == 0 {
-                            store.complete_pending(false);
+                            session.complete_pending(false);
                         }
                     }
-                    store.upsert(&*keys.get(i as usize).unwrap(), &42, i as u64);
+                    let key = keys.get(i as usize).unwrap();
+                    if pod {
+                        session.upsert_pod(key, &42);
+                    } else {
+                        session.upsert(key, &42);
+                    }
                 }
                 chunk_idx = idx.fetch_add(K_CHUNK_SIZE, Ordering::SeqCst);
             }
-            store.complete_pending(true);
-            store.stop_session();
         }));
     }
     for t in threads {
@@ -192,6 +202,7 @@ pub fn run_benchmark<F: Fn(usize) -> Operation + Send + Copy + 'static>(
     keys: &Arc<Vec<u64>>,
     num_threads: u8,
     op_allocator: F,
+    pod: bool,
 ) {
     let topo = Arc::new(Mutex::new(Topology::new()));
     let idx = Arc::new(AtomicUsize::new(0));
@@ -225,7 +236,7 @@ pub fn run_benchmark<F: Fn(usize) -> Operation + Send + Copy + 'static>(
                     let mut upserts = 0;
                     let mut rmws = 0;
 
-                    let _session = store.start_session();
+                    let session = store.start_session();
 
                     barrier.wait();
                     let start = Instant::now();
@@ -239,31 +250,43 @@ pub fn run_benchmark<F: Fn(usize) -> Operation + Send + Copy + 'static>(
                         }
                         for i in chunk_idx..(chunk_idx + K_CHUNK_SIZE) {
                             if i % K_REFRESH_INTERVAL == 0 {
-                                store.refresh();
+                                session.refresh();
                                 if i % K_COMPLETE_PENDING_INTERVAL == 0 {
-                                    store.complete_pending(false);
+                                    session.complete_pending(false);
                                 }
                             }
-                            match op_allocator(i) {
-                                Operation::Read => {
-                                    let (_, _): (u8, Receiver<i32>) =
-                                        store.read(&*keys.get(i).unwrap(), 1);
+                            let key = keys.get(i).unwrap();
+                            match (op_allocator(i), pod) {
+                                (Operation::Read, false) => {
+                                    let (_, _): (u8, Receiver<ReadResult<i32>>) =
+                                        session.read(key);
+                                    reads += 1;
+                                }
+                                (Operation::Read, true) => {
+                                    session.read_pod(key, |_: ReadResult<i32>| {});
                                     reads += 1;
                                 }
-                                Operation::Upsert => {
-                                    store.upsert(&*keys.get(i).unwrap(), &42, 1);
+                                (Operation::Upsert, false) => {
+                                    session.upsert(key, &42);
+                                    upserts += 1;
+                                }
+                                (Operation::Upsert, true) => {
+                                    session.upsert_pod(key, &42);
                                     upserts += 1;
                                 }
-                                Operation::Rmw => {
-                                    store.rmw(&*keys.get(i).unwrap(), &5, 1);
+                                (Operation::Rmw, false) => {
+                                    session.rmw(key, &5);
+                                    rmws += 1;
+                                }
+                                (Operation::Rmw, true) => {
+                                    session.rmw_pod(key, &5);
                                     rmws += 1;
                                 }
                             }
                         }
                     }
 
-                    store.complete_pending(true);
-                    store.stop_session();
+                    drop(session);
                     let duration = Instant::now().duration_since(start);
 
                     println!(
@@ -283,20 +306,19 @@ pub fn run_benchmark<F: Fn(usize) -> Operation + Send + Copy + 'static>(
 
     barrier.wait();
     let start = Instant::now();
-    let mut last_checkpoint = Instant::now();
-    let mut num_checkpoints = 0;
+    // Checkpoints are taken in the background by the store's checkpoint schedule
+    let started = SystemTime::now();
 
     while Instant::now().duration_since(start).as_secs() < K_RUN_TIME {
         std::thread::sleep(Duration::from_secs(1));
-        if Instant::now().duration_since(last_checkpoint).as_secs() > K_CHECKPOINT_SECONDS {
-            println!("Checkpointing...");
-            store.checkpoint();
-            num_checkpoints += 1;
-            last_checkpoint = Instant::now();
-        }
     }
 
     done.store(true, Ordering::SeqCst);
+    let num_checkpoints = store
+        .checkpoint_history()
+        .iter()
+        .filter(|record| record.started >= started)
+        .count();
 
     let mut total_counts = (0, 0, 0, 0);
     for t in threads {
@@ -2,7 +2,8 @@ extern crate clap;
 
 use benchmark::*;
 use clap::{App, Arg, SubCommand};
-use faster_rs::FasterKvBuilder;
+use faster_rs::{CheckpointSchedule, FasterKvBuilder};
+use std::time::Duration;
 use std::sync::Arc;
 
 fn main() {
@@ -46,7 +47,12 @@ fn main() {
                     "read_upsert_50_50",
                     "rmw_100",
                     "upsert_100",
-                ])),
+                ]))
+                .arg(
+                    Arg::with_name("pod")
+                        .long("pod")
+                        .help("Use fixed-size Pod records instead of serialized keys and values"),
+                ),
         )
         .subcommand(
             SubCommand::with_name("generate-keys")
@@ -89,6 +95,7 @@ fn main() {
         let workload = matches
             .value_of("workload")
             .expect("Workload not specified");
+        let pod = matches.is_present("pod");
         let op_allocator = match workload {
             "read_upsert_50_50" => read_upsert5050,
             "rmw_100" => rmw_100,
@@ -99,14 +106,22 @@ fn main() {
         let table_size: u64 = 134217728;
         let log_size: u64 = 17179869184;
         let dir_path = String::from("benchmark_store");
-        let store = Arc::new(FasterKvBuilder::new(table_size, log_size).with_disk(&dir_path).build().unwrap());
+        let schedule = CheckpointSchedule::every(Duration::from_secs(K_CHECKPOINT_SECONDS))
+            .on_durable(|record| println!("Checkpoint {:?} is durable", record.token));
+        let store = Arc::new(
+            FasterKvBuilder::new(table_size, log_size)
+                .with_disk(&dir_path)
+                .with_checkpoint_schedule(schedule)
+                .build()
+                .unwrap(),
+        );
         let (load_keys, txn_keys) = load_files(load_keys_file, run_keys_file);
         let load_keys = Arc::new(load_keys);
         let txn_keys = Arc::new(txn_keys);
         println!("Populating datastore");
-        populate_store(&store, &load_keys, num_threads);
+        populate_store(&store, &load_keys, num_threads, pod);
         println!("Beginning benchmark");
-        run_benchmark(&store, &txn_keys, num_threads, op_allocator);
+        run_benchmark(&store, &txn_keys, num_threads, op_allocator, pod);
         match store.clean_storage() {
             Ok(_) => { /*no-op*/ }
             Err(_) => eprintln!("Unable to clear storage"),
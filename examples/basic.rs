@@ -1,6 +1,6 @@
 extern crate faster_rs;
 
-use faster_rs::{status, FasterKv};
+use faster_rs::{status, FasterKv, ReadResult};
 use std::sync::mpsc::Receiver;
 
 fn main() {
@@ -26,10 +26,11 @@ fn main() {
 
     // Read
     for i in 0..1000 {
-        // Note: need to provide type annotation for the Receiver
-        let (read, recv): (u8, Receiver<u64>) = store.read(&(key0 + i), i);
+        // Note: need to provide type annotation for the Receiver, which yields
+        // Ok(None) if the key does not exist
+        let (read, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&(key0 + i), i);
         assert!(read == status::OK || read == status::PENDING);
-        let val = recv.recv().unwrap();
+        let val = recv.recv().unwrap().unwrap().expect("Key not found");
         assert_eq!(val, value0 + i + modification);
         println!("Key: {}, Value: {}", key0 + i, val);
     }
@@ -1,7 +1,7 @@
 extern crate faster_rs;
 extern crate serde_derive;
 
-use faster_rs::{status, FasterKv};
+use faster_rs::{status, FasterKv, ReadResult};
 use serde_derive::{Deserialize, Serialize};
 use std::sync::mpsc::Receiver;
 
@@ -27,10 +27,11 @@ fn main() {
 
     assert!(store.size() > 0);
 
-    // Note: need to provide type annotation for the Receiver
-    let (read, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    // Note: need to provide type annotation for the Receiver, which yields
+    // Ok(None) if the key does not exist
+    let (read, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(read == status::OK || read == status::PENDING);
-    let val = recv.recv().unwrap();
+    let val = recv.recv().unwrap().unwrap().expect("Key not found");
     println!("Key: {:?}, Value: {}", key, val);
 
     // Clear used storage
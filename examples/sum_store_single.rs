@@ -28,16 +28,14 @@ fn main() {
             );
             populate();
         } else if operation == "recover" {
-            if args.len() > 2 {
-                let token = &args[2];
-                recover(token.to_string());
-            } else {
-                println!("Second argument required is token checkpoint to recover");
-            }
+            recover(args.get(2).cloned());
         }
     } else {
         println!("Populate: args {}", "1. populate");
-        println!("Recover: args {}, {}", "1. recover", "2. checkpoint token");
+        println!(
+            "Recover: args {}, {}",
+            "1. recover", "2. checkpoint token (optional, defaults to the latest)"
+        );
     }
 }
 
@@ -49,11 +47,13 @@ fn populate() -> () {
     {
         // Populate Store
         let session = store.start_session();
-        println!("Starting Session {}", session);
+        println!("Starting Session {}", session.id());
 
         for i in 0..NUM_OPS {
             let idx = i as u64;
-            store.rmw(&(idx % NUM_UNIQUE_KEYS), &(1 as u64), idx);
+            session
+                .rmw_with_serial(&(idx % NUM_UNIQUE_KEYS), &(1 as u64), idx + 1)
+                .unwrap();
 
             if (idx % CHECKPOINT_INTERVAL) == 0 {
                 let check = store.checkpoint().unwrap();
@@ -61,75 +61,71 @@ fn populate() -> () {
             }
 
             if (idx % COMPLETE_PENDING_INTERVAL) == 0 {
-                store.complete_pending(false);
+                session.complete_pending(false);
             } else if (idx % REFRESH_INTERVAL) == 0 {
-                store.refresh();
+                session.refresh();
             }
         }
 
         println!("Dumping distribution");
         store.dump_distribution();
-        println!("Stopping Session {}", session);
-        store.complete_pending(true);
-        store.stop_session();
+        println!("Stopping Session {}", session.id());
+        drop(session);
         println!("Store size: {}", store.size());
     } else {
         println!("Failed to create FasterKV store");
     }
 }
 
-fn recover(token: String) -> () {
+fn recover(token: Option<String>) -> () {
     println!("Attempting to recover");
-    if let Ok(recover_store) = FasterKvBuilder::new(TABLE_SIZE, LOG_SIZE)
-        .with_disk(STORAGE_DIR)
-        .set_pre_allocate_log(true)
-        .build()
-    {
-        match recover_store.recover(token.clone(), token.clone()) {
-            Ok(rec) => {
-                println!("Recover version: {}", rec.version);
-                println!("Recover status: {}", rec.status);
-                println!("Recovered sessions: {:?}", rec.session_ids);
-                let persisted_count =
-                    recover_store.continue_session(rec.session_ids.first().cloned().unwrap());
-                println!("Session persisted until: {}", persisted_count);
+    let mut builder = FasterKvBuilder::new(TABLE_SIZE, LOG_SIZE);
+    builder.with_disk(STORAGE_DIR).set_pre_allocate_log(true);
+    if let Some(token) = &token {
+        builder.with_recovery_tokens(token, token);
+    }
+    match builder.open_or_recover() {
+        Ok((recover_store, Some(rec))) => {
+            println!("Recover version: {}", rec.version);
+            println!("Recover status: {}", rec.status);
+            println!("Recovered sessions: {:?}", rec.session_ids);
+            let session = recover_store.continue_session(rec.session_ids.first().cloned().unwrap());
+            let persisted_count = rec.sessions[0].1;
+            println!("Session persisted until: {}", persisted_count);
 
-                let mut expected_results = Vec::with_capacity(NUM_UNIQUE_KEYS as usize);
-                expected_results.resize(NUM_UNIQUE_KEYS as usize, 0);
-                for i in 0..(persisted_count + 1) {
-                    let elem = expected_results
-                        .get_mut((i % NUM_UNIQUE_KEYS) as usize)
-                        .unwrap();
-                    *elem += 1;
-                }
+            let mut expected_results = Vec::with_capacity(NUM_UNIQUE_KEYS as usize);
+            expected_results.resize(NUM_UNIQUE_KEYS as usize, 0);
+            for i in 0..(persisted_count + 1) {
+                let elem = expected_results
+                    .get_mut((i % NUM_UNIQUE_KEYS) as usize)
+                    .unwrap();
+                *elem += 1;
+            }
 
-                println!("Verifying recovered values!");
-                let mut incorrect = 0;
-                for i in 0..NUM_OPS {
-                    let idx = i as u64;
-                    let (status, recv): (u8, Receiver<u64>) =
-                        recover_store.read(&(idx % NUM_UNIQUE_KEYS), idx);
-                    if let Ok(val) = recv.recv() {
-                        let expected = *expected_results
-                            .get((idx % NUM_UNIQUE_KEYS) as usize)
-                            .unwrap();
-                        if expected != val {
-                            println!(
-                                "Error recovering {}, expected {}, got {}",
-                                idx, expected, val
-                            );
-                            incorrect += 1;
-                        }
-                    } else {
-                        println!("Failure to read with status: {}, and key: {}", status, idx);
+            println!("Verifying recovered values!");
+            let mut incorrect = 0;
+            for i in 0..NUM_OPS {
+                let idx = i as u64;
+                let (status, recv): (u8, Receiver<ReadResult<u64>>) =
+                    session.read(&(idx % NUM_UNIQUE_KEYS));
+                if let Ok(Ok(Some(val))) = recv.recv() {
+                    let expected = *expected_results
+                        .get((idx % NUM_UNIQUE_KEYS) as usize)
+                        .unwrap();
+                    if expected != val {
+                        println!(
+                            "Error recovering {}, expected {}, got {}",
+                            idx, expected, val
+                        );
+                        incorrect += 1;
                     }
+                } else {
+                    println!("Failure to read with status: {}, and key: {}", status, idx);
                 }
-                println!("{} incorrect recoveries", incorrect);
-                recover_store.stop_session();
             }
-            Err(_) => println!("Recover operation failed"),
+            println!("{} incorrect recoveries", incorrect);
         }
-    } else {
-        println!("{}", "Failed to create recover store");
+        Ok((_, None)) => println!("No checkpoints found to recover from"),
+        Err(_) => println!("Recover operation failed"),
     }
 }
@@ -2,7 +2,8 @@ extern crate bindgen;
 
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use cmake::Config;
 
 // Credit to: https://github.com/rust-rocksdb/rust-rocksdb/blob/master/librocksdb-sys/build.rs
@@ -19,7 +20,7 @@ fn fail_on_empty_directory(name: &str) {
 
 fn faster_bindgen() {
     let bindings = bindgen::Builder::default()
-        .header("FASTER/cc/src/core/faster-c.h")
+        .header("faster-c/faster-c.h")
         .blacklist_type("max_align_t") // https://github.com/rust-lang-nursery/rust-bindgen/issues/550
         .ctypes_prefix("libc")
         .generate()
@@ -31,12 +32,56 @@ fn faster_bindgen() {
         .expect("unable to write faster bindings");
 }
 
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()));
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+// Applies the patches in order to a fresh copy of FASTER's sources in OUT_DIR, leaving the
+// submodule untouched, and returns the copy's include directory
+fn patched_sources() -> PathBuf {
+    let patched = PathBuf::from(env::var("OUT_DIR").unwrap()).join("FASTER");
+    if patched.exists() {
+        fs::remove_dir_all(&patched).unwrap();
+    }
+    copy_dir(Path::new("FASTER/cc/src"), &patched.join("cc/src"));
+
+    let mut patches: Vec<PathBuf> = fs::read_dir("patches")
+        .unwrap()
+        .map(|entry| fs::canonicalize(entry.unwrap().path()).unwrap())
+        .collect();
+    patches.sort();
+    for patch in patches {
+        let status = Command::new("patch")
+            .args(&["--batch", "--forward", "--strip=1", "--input"])
+            .arg(&patch)
+            .current_dir(&patched)
+            .status()
+            .expect("unable to run patch, is it installed?");
+        assert!(
+            status.success(),
+            "unable to apply {} to FASTER, is the submodule checked out at the commit it pins?",
+            patch.display()
+        );
+    }
+    patched.join("cc/src")
+}
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=FASTER/");
+    println!("cargo:rerun-if-changed=faster-c/");
+    println!("cargo:rerun-if-changed=patches/");
 
     fail_on_empty_directory("FASTER");
+    let faster_src = patched_sources();
 
     faster_bindgen();
 
@@ -44,6 +89,14 @@ fn main() {
         .cflag("--std=c++11 ")
         .build();
 
+    // The C interface is kept here rather than in FASTER, and links against its library
+    cc::Build::new()
+        .cpp(true)
+        .flag("-std=c++14")
+        .include(faster_src)
+        .file("faster-c/faster-c.cc")
+        .compile("faster-c");
+
     println!("cargo:rustc-link-search=native={}/{}", dst.display(), "build");
     // Fix this...
     println!("cargo:rustc-link-lib=static=faster");
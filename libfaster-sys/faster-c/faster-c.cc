@@ -0,0 +1,1436 @@
+// C interface to FASTER's key-value store, see faster-c.h
+
+#include <algorithm>
+#include <atomic>
+#include <cstdlib>
+#include <cstring>
+#include <mutex>
+#include <string>
+#include <thread>
+#include <type_traits>
+#include <unordered_set>
+#include <vector>
+
+#include "faster-c.h"
+#include "core/faster.h"
+#include "core/log_scan.h"
+#include "device/file_system_disk.h"
+#include "device/null_disk.h"
+
+// Exported by the Rust crate to free the buffers it hands over
+extern "C" void deallocate_vec(uint8_t* vec, uint64_t length);
+
+using namespace FASTER::core;
+
+namespace {
+
+// Bytes borrowed from the caller. A copy owns its bytes, so an operation that goes pending,
+// which FASTER copies to the heap, does not depend on the caller's buffer outliving the call.
+class Bytes {
+ public:
+  Bytes(const uint8_t* data, uint64_t length)
+    : owned_{}
+    , data_{ data }
+    , length_{ length } {
+  }
+  Bytes(const Bytes& other)
+    : owned_(other.data_, other.data_ + other.length_)
+    , data_{ owned_.data() }
+    , length_{ other.length_ } {
+  }
+  Bytes& operator=(const Bytes&) = delete;
+
+  inline const uint8_t* data() const {
+    return data_;
+  }
+  inline uint64_t length() const {
+    return length_;
+  }
+
+ private:
+  std::vector<uint8_t> owned_;
+  const uint8_t* data_;
+  uint64_t length_;
+};
+
+// FASTER's Utility::HashBytes, over single bytes rather than pairs of them
+inline uint64_t hash_bytes(const uint8_t* data, uint64_t length) {
+  const uint64_t kMagicNum = 40343;
+  uint64_t state = length;
+  for(uint64_t idx = 0; idx < length; ++idx) {
+    state = kMagicNum * state + data[idx];
+  }
+  return Utility::Rotr64(kMagicNum * state, 6);
+}
+
+// In a record, a key is its length followed by its bytes. Outside the log it points at
+// bytes held by an operation's context, which write_deep_key_at copies into the record.
+class Key {
+ public:
+  Key(const uint8_t* data, uint64_t length)
+    : length_{ length }
+    , data_{ data } {
+  }
+  Key(const Key& other)
+    : length_{ other.length_ }
+    , data_{ other.data() } {
+  }
+
+  inline uint32_t size() const {
+    return static_cast<uint32_t>(sizeof(Key) + length_);
+  }
+  inline KeyHash GetHash() const {
+    return KeyHash{ hash_bytes(data(), length_) };
+  }
+  inline bool operator==(const Key& other) const {
+    return length_ == other.length_ && std::memcmp(data(), other.data(), length_) == 0;
+  }
+  inline bool operator!=(const Key& other) const {
+    return !(*this == other);
+  }
+  // Also writes the header of the value that follows the key, see Value
+  inline void write_deep_key_at(Key* dst) const;
+
+  inline const uint8_t* data() const {
+    return data_ != nullptr ? data_ : buffer();
+  }
+  inline uint64_t length() const {
+    return length_;
+  }
+
+ private:
+  inline const uint8_t* buffer() const {
+    return reinterpret_cast<const uint8_t*>(this + 1);
+  }
+  inline uint8_t* buffer() {
+    return reinterpret_cast<uint8_t*>(this + 1);
+  }
+
+  uint64_t length_;
+  // Null once the key has been written to a record
+  const uint8_t* data_;
+};
+
+// Generation lock serializing the in-place updates of a value. Readers copy the value and
+// retry if its generation changed meanwhile. A value whose record has been superseded by a
+// copy is marked replaced, after which it is never updated in place again.
+class ValueLock {
+ public:
+  ValueLock()
+    : control_{ 0 } {
+  }
+
+  inline void Reset() {
+    control_.store(0);
+  }
+  // Waits for other in-place updates to finish. Returns false if the value was replaced.
+  inline bool Lock() {
+    uint64_t control = control_.load();
+    while(true) {
+      if((control & kReplaced) != 0) {
+        return false;
+      }
+      if((control & kLocked) == 0 && control_.compare_exchange_weak(control, control | kLocked)) {
+        return true;
+      }
+      std::this_thread::yield();
+      control = control_.load();
+    }
+  }
+  // Starts a new generation, in which the value can no longer be updated if `replaced`
+  inline void Unlock(bool replaced) {
+    uint64_t generation = (control_.load() + 1) & kGeneration;
+    control_.store(generation | (replaced ? kReplaced : 0));
+  }
+  // Waits for an in-place update to finish and returns the value's generation
+  inline uint64_t WaitUnlocked() const {
+    uint64_t control = control_.load();
+    while((control & kLocked) != 0) {
+      std::this_thread::yield();
+      control = control_.load();
+    }
+    return control;
+  }
+  inline bool Changed(uint64_t generation) const {
+    return control_.load() != generation;
+  }
+
+ private:
+  static constexpr uint64_t kLocked = (uint64_t)1 << 62;
+  static constexpr uint64_t kReplaced = (uint64_t)1 << 63;
+  static constexpr uint64_t kGeneration = kLocked - 1;
+
+  std::atomic<uint64_t> control_;
+};
+
+// In a record, a value is this header followed by its bytes. The space reserved for the
+// value does not change, so an in-place update must fit in it.
+//
+// FASTER writes nothing after the key of the record a Delete creates, so writing the key
+// also writes the header of an empty value. Every other new record's context initializes
+// its value again.
+//
+// FASTER creates a record for every Read-Modify-Write of a missing key. When faster_rmw_with
+// decides not to create the key, that record is marked absent and treated as missing.
+class Value {
+ public:
+  Value()
+    : lock_{}
+    , size_{ 0 }
+    , length_{ 0 } {
+  }
+
+  static inline uint32_t size_for(uint64_t length) {
+    return static_cast<uint32_t>(sizeof(Value) + length);
+  }
+
+  inline uint32_t size() const {
+    return size_;
+  }
+  // Prepares the value of a new record, which was allocated with the given size
+  inline void Initialize(uint32_t size) {
+    lock_.Reset();
+    size_ = size;
+    length_ = 0;
+  }
+
+  inline uint32_t capacity() const {
+    return size_ - static_cast<uint32_t>(sizeof(Value));
+  }
+  inline uint32_t length() const {
+    return length_;
+  }
+  inline void set_length(uint64_t length) {
+    length_ = static_cast<uint32_t>(length);
+  }
+  inline const uint8_t* data() const {
+    return reinterpret_cast<const uint8_t*>(this + 1);
+  }
+  inline uint8_t* data() {
+    return reinterpret_cast<uint8_t*>(this + 1);
+  }
+  inline void Write(const uint8_t* bytes, uint64_t length) {
+    std::memcpy(data(), bytes, length);
+    set_length(length);
+  }
+  inline bool absent() const {
+    return length_ == kAbsent;
+  }
+  inline void MarkAbsent() {
+    length_ = kAbsent;
+  }
+
+  inline ValueLock& lock() const {
+    return lock_;
+  }
+  // Stops in-place updates of a value that is about to be copied to a new record
+  inline void MarkReplaced() const {
+    if(lock_.Lock()) {
+      lock_.Unlock(true);
+    }
+  }
+
+ private:
+  static constexpr uint32_t kAbsent = UINT32_MAX;
+
+  mutable ValueLock lock_;
+  uint32_t size_;
+  uint32_t length_;
+};
+
+inline void Key::write_deep_key_at(Key* dst) const {
+  dst->length_ = length_;
+  dst->data_ = nullptr;
+  std::memcpy(dst->buffer(), data(), length_);
+  // Where FASTER's Record places the value
+  void* value = reinterpret_cast<uint8_t*>(dst) + pad_alignment(size(), alignof(Value));
+  new(value) Value{};
+  static_cast<Value*>(value)->Initialize(Value::size_for(0));
+}
+
+// Copies a value of the mutable region, which may be updated in place meanwhile. Returns
+// false if the value is absent.
+inline bool copy_value(const Value& value, std::vector<uint8_t>& copy) {
+  bool absent;
+  uint64_t generation;
+  do {
+    generation = value.lock().WaitUnlocked();
+    absent = value.absent();
+    if(!absent) {
+      uint32_t length = std::min(value.length(), value.capacity());
+      copy.assign(value.data(), value.data() + length);
+    }
+  } while(value.lock().Changed(generation));
+  return !absent;
+}
+
+class UpsertContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  UpsertContext(const uint8_t* key, uint64_t key_length, const uint8_t* value,
+                uint64_t value_length)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , value_{ value, value_length } {
+  }
+  UpsertContext(const UpsertContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , value_{ other.value_ } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  inline uint32_t value_size() const {
+    return Value::size_for(value_.length());
+  }
+  inline void Put(Value& value) {
+    value.Initialize(value_size());
+    value.Write(value_.data(), value_.length());
+  }
+  inline bool PutAtomic(Value& value) {
+    if(!value.lock().Lock()) {
+      return false;
+    }
+    if(value.capacity() < value_.length()) {
+      // FASTER appends a new record instead
+      value.lock().Unlock(true);
+      return false;
+    }
+    value.Write(value_.data(), value_.length());
+    value.lock().Unlock(false);
+    return true;
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  Bytes key_bytes_;
+  Key key_;
+  Bytes value_;
+};
+
+class ReadContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  ReadContext(const uint8_t* key, uint64_t key_length, read_callback cb, void* target)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , cb_{ cb }
+    , target_{ target }
+    , found_{ true } {
+  }
+  ReadContext(const ReadContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , cb_{ other.cb_ }
+    , target_{ other.target_ }
+    , found_{ other.found_ } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  // Records outside the mutable region are never updated
+  inline void Get(const Value& value) {
+    if(value.absent()) {
+      Missing();
+      return;
+    }
+    cb_(target_, value.data(), value.length(), Ok);
+  }
+  // A mutable record may be updated in place meanwhile, so the value is copied out first
+  inline void GetAtomic(const Value& value) {
+    std::vector<uint8_t> copy;
+    if(!copy_value(value, copy)) {
+      Missing();
+      return;
+    }
+    cb_(target_, copy.data(), copy.size(), Ok);
+  }
+  inline void Fail(Status status) {
+    cb_(target_, nullptr, 0, static_cast<faster_status>(status));
+  }
+  // Whether the record found holds a value
+  inline bool found() const {
+    return found_;
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  Bytes key_bytes_;
+  Key key_;
+  inline void Missing() {
+    found_ = false;
+    Fail(Status::NotFound);
+  }
+
+  read_callback cb_;
+  void* target_;
+  bool found_;
+};
+
+class RmwContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  RmwContext(const uint8_t* key, uint64_t key_length, const uint8_t* modification,
+             uint64_t length, rmw_initial_callback initial, rmw_in_place_callback in_place,
+             rmw_callback cb)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ modification, length }
+    , initial_{ initial }
+    , in_place_{ in_place }
+    , cb_{ cb } {
+  }
+  RmwContext(const RmwContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ other.modification_ }
+    , initial_{ other.initial_ }
+    , in_place_{ other.in_place_ }
+    , cb_{ other.cb_ } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  inline uint32_t value_size() const {
+    return Value::size_for(initial_(modification_.data(), modification_.length(), nullptr));
+  }
+  inline uint32_t value_size(const Value& old_value) const {
+    if(old_value.absent()) {
+      return value_size();
+    }
+    return Value::size_for(Update(old_value, nullptr));
+  }
+  inline void RmwInitial(Value& value) {
+    value.Initialize(value_size());
+    value.set_length(initial_(modification_.data(), modification_.length(), value.data()));
+  }
+  inline void RmwCopy(const Value& old_value, Value& value) {
+    if(old_value.absent()) {
+      RmwInitial(value);
+      return;
+    }
+    value.Initialize(value_size(old_value));
+    value.set_length(Update(old_value, value.data()));
+  }
+  // The value is updated while it is locked, so concurrent updates of the key are applied
+  // one after the other and readers never see it partly written. If the callback cannot
+  // update it, the value is marked replaced and FASTER copies it to a new record.
+  inline bool RmwAtomic(Value& value) {
+    if(!value.lock().Lock()) {
+      return false;
+    }
+    if(value.absent() || !in_place_(value.data(), value.length(), modification_.data(),
+                                    modification_.length())) {
+      value.lock().Unlock(true);
+      return false;
+    }
+    value.lock().Unlock(false);
+    return true;
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  inline uint64_t Update(const Value& old_value, uint8_t* dst) const {
+    return cb_(old_value.data(), old_value.length(),
+               const_cast<uint8_t*>(modification_.data()), modification_.length(), dst);
+  }
+
+  Bytes key_bytes_;
+  Key key_;
+  Bytes modification_;
+  rmw_initial_callback initial_;
+  rmw_in_place_callback in_place_;
+  rmw_callback cb_;
+};
+
+// A Read-Modify-Write whose update is a callback together with a context owned by the
+// caller. A missing key is either created with the modification as its value or, if
+// `update_missing` is set, passed to the callback as a null current value. The callback
+// may return RMW_UNCHANGED to keep the current value, or not to create a missing key.
+class ContextRmwContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  ContextRmwContext(const uint8_t* key, uint64_t key_length, const uint8_t* modification,
+                    uint64_t length, bool update_missing, rmw_context_callback cb,
+                    void* context, release_callback release)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ modification, length }
+    , update_missing_{ update_missing }
+    , cb_{ cb }
+    , context_{ context }
+    , release_{ release } {
+  }
+  ContextRmwContext(const ContextRmwContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ other.modification_ }
+    , update_missing_{ other.update_missing_ }
+    , cb_{ other.cb_ }
+    , context_{ other.context_ }
+    , release_{ other.release_ } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  inline uint32_t value_size() const {
+    uint64_t length = Initial(nullptr);
+    return Value::size_for(length == RMW_UNCHANGED ? 0 : length);
+  }
+  inline uint32_t value_size(const Value& old_value) const {
+    if(old_value.absent()) {
+      return value_size();
+    }
+    uint64_t length = Update(old_value, nullptr);
+    return Value::size_for(length == RMW_UNCHANGED ? old_value.length() : length);
+  }
+  inline void RmwInitial(Value& value) {
+    value.Initialize(value_size());
+    uint64_t length = Initial(value.data());
+    if(length == RMW_UNCHANGED) {
+      value.MarkAbsent();
+    } else {
+      value.set_length(length);
+    }
+  }
+  inline void RmwCopy(const Value& old_value, Value& value) {
+    if(old_value.absent()) {
+      RmwInitial(value);
+      return;
+    }
+    value.Initialize(value_size(old_value));
+    uint64_t length = Update(old_value, value.data());
+    if(length == RMW_UNCHANGED) {
+      value.Write(old_value.data(), old_value.length());
+    } else {
+      value.set_length(length);
+    }
+  }
+  // The callback can only write a new value, so every update is a copy
+  inline bool RmwAtomic(Value& value) {
+    value.MarkReplaced();
+    return false;
+  }
+  // Hands the context back to the caller once the operation has finished
+  inline void Release() {
+    release_(context_);
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  inline uint64_t Initial(uint8_t* dst) const {
+    if(!update_missing_) {
+      if(dst != nullptr) {
+        std::memcpy(dst, modification_.data(), modification_.length());
+      }
+      return modification_.length();
+    }
+    return cb_(context_, nullptr, 0, modification_.data(), modification_.length(), dst);
+  }
+  inline uint64_t Update(const Value& old_value, uint8_t* dst) const {
+    return cb_(context_, old_value.data(), old_value.length(), modification_.data(),
+               modification_.length(), dst);
+  }
+
+  Bytes key_bytes_;
+  Key key_;
+  Bytes modification_;
+  bool update_missing_;
+  rmw_context_callback cb_;
+  void* context_;
+  release_callback release_;
+};
+
+// A Read-Modify-Write of a fixed-size value. A missing key, or a record holding a value
+// of another size, is created by the initial callback.
+class PodRmwContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  PodRmwContext(const uint8_t* key, uint64_t key_length, const uint8_t* modification,
+                uint64_t value_length, rmw_pod_initial_callback initial, rmw_pod_callback cb)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ modification, value_length }
+    , initial_{ initial }
+    , cb_{ cb } {
+  }
+  PodRmwContext(const PodRmwContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() }
+    , modification_{ other.modification_ }
+    , initial_{ other.initial_ }
+    , cb_{ other.cb_ } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  inline uint32_t value_size() const {
+    return Value::size_for(modification_.length());
+  }
+  inline uint32_t value_size(const Value& old_value) const {
+    return value_size();
+  }
+  inline void RmwInitial(Value& value) {
+    value.Initialize(value_size());
+    initial_(modification_.data(), value.data());
+    value.set_length(modification_.length());
+  }
+  inline void RmwCopy(const Value& old_value, Value& value) {
+    if(old_value.length() != modification_.length()) {
+      RmwInitial(value);
+      return;
+    }
+    value.Initialize(value_size());
+    cb_(old_value.data(), modification_.data(), value.data());
+    value.set_length(modification_.length());
+  }
+  // Updating in place would let readers see a partly written value, so every update is
+  // a copy
+  inline bool RmwAtomic(Value& value) {
+    value.MarkReplaced();
+    return false;
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  Bytes key_bytes_;
+  Key key_;
+  Bytes modification_;
+  rmw_pod_initial_callback initial_;
+  rmw_pod_callback cb_;
+};
+
+class DeleteContext : public IAsyncContext {
+ public:
+  typedef Key key_t;
+  typedef Value value_t;
+
+  DeleteContext(const uint8_t* key, uint64_t key_length)
+    : key_bytes_{ key, key_length }
+    , key_{ key_bytes_.data(), key_bytes_.length() } {
+  }
+  DeleteContext(const DeleteContext& other)
+    : key_bytes_{ other.key_bytes_ }
+    , key_{ key_bytes_.data(), key_bytes_.length() } {
+  }
+
+  inline const Key& key() const {
+    return key_;
+  }
+  // The tombstone's value is empty
+  inline uint32_t value_size() const {
+    return Value::size_for(0);
+  }
+
+ protected:
+  Status DeepCopy_Internal(IAsyncContext*& context_copy) {
+    return IAsyncContext::DeepCopy_Internal(*this, context_copy);
+  }
+
+ private:
+  Bytes key_bytes_;
+  Key key_;
+};
+
+typedef FASTER::environment::QueueIoHandler handler_t;
+typedef FASTER::device::FileSystemDisk<handler_t, 1073741824ull> disk_t;
+typedef FasterKv<Key, Value, disk_t> disk_store_t;
+typedef FasterKv<Key, Value, FASTER::device::NullDisk> memory_store_t;
+
+// A growth of the index that has not finished yet, see faster_grow_index_with_callback
+struct PendingGrowth {
+  std::mutex mutex;
+  bool growing;
+  grow_callback cb;
+  void* context;
+  release_callback release;
+};
+
+}  // namespace
+
+struct faster_t {
+  // Exactly one of the two is set
+  memory_store_t* memory_store;
+  disk_store_t* disk_store;
+  PendingGrowth growth;
+};
+
+// A scan of the log, see faster_scan_next
+struct faster_scan_t {
+  virtual ~faster_scan_t() {
+  }
+  // Moves to the next record, returning false once the scan has visited all of them
+  virtual bool Next() = 0;
+
+  // The current record, copied out of the log so it stays valid until the next call
+  std::vector<uint8_t> key;
+  std::vector<uint8_t> value;
+  uint64_t address;
+  bool deleted;
+};
+
+namespace {
+
+// The store whose call this thread is running, which FASTER's callbacks cannot be told
+thread_local faster_t* current_store = nullptr;
+
+// Sets the thread's current store until it goes out of scope
+class CurrentStore {
+ public:
+  explicit CurrentStore(faster_t* store)
+    : previous_{ current_store } {
+    current_store = store;
+  }
+  ~CurrentStore() {
+    current_store = previous_;
+  }
+
+ private:
+  faster_t* previous_;
+};
+
+// Runs `op` on the store, whichever kind of disk it uses
+template <class Op>
+inline auto with_store(faster_t* store, Op&& op) -> decltype(op(*store->disk_store)) {
+  CurrentStore current{ store };
+  if(store->disk_store != nullptr) {
+    return op(*store->disk_store);
+  }
+  return op(*store->memory_store);
+}
+
+// The stores this thread has started a session with
+thread_local std::vector<const faster_t*> thread_sessions;
+
+inline bool has_session(const faster_t* store) {
+  return std::find(thread_sessions.begin(), thread_sessions.end(), store) !=
+         thread_sessions.end();
+}
+
+// Gives the calling thread a session with the store for as long as it lives, unless the
+// thread already has one
+class ScopedSession {
+ public:
+  explicit ScopedSession(faster_t* store)
+    : store_{ store }
+    , started_{ !has_session(store) } {
+    if(started_) {
+      with_store(store_, [](auto& store) {
+        store.StartSession();
+      });
+      thread_sessions.push_back(store_);
+    }
+  }
+  ScopedSession(const ScopedSession&) = delete;
+  ScopedSession& operator=(const ScopedSession&) = delete;
+
+  ~ScopedSession() {
+    if(started_) {
+      with_store(store_, [](auto& store) {
+        store.CompletePending(true);
+        store.StopSession();
+      });
+      thread_sessions.erase(std::find(thread_sessions.begin(), thread_sessions.end(), store_));
+    }
+  }
+
+ private:
+  faster_t* store_;
+  bool started_;
+};
+
+// Scans the log of a store with FASTER's ScanIterator, which reads the pages that are no
+// longer in memory from disk
+template <class S>
+class StoreScan : public faster_scan_t {
+ public:
+  StoreScan(faster_t* faster, S& store, Address from, Address until)
+    : session_{ faster }
+    , store_{ store }
+    , iterator_{ &store.hlog, Buffering::DOUBLE_PAGE, from, until, &store.disk }
+    , next_{ from } {
+  }
+
+  bool Next() override {
+    typename S::record_t* record;
+    do {
+      record = iterator_.GetNext();
+      if(record == nullptr) {
+        return false;
+      }
+      address = Locate(record->size()).control();
+    } while(record->header.invalid);
+
+    const Key& record_key = record->key();
+    key.assign(record_key.data(), record_key.data() + record_key.length());
+    const Value& record_value = record->value();
+    // Like reads, only copies values of the mutable region under their lock
+    deleted = record->header.tombstone;
+    if(!deleted && Address{ address } >= store_.hlog.read_only_address.load()) {
+      deleted = !copy_value(record_value, value);
+    } else if(!deleted && !record_value.absent()) {
+      value.assign(record_value.data(), record_value.data() + record_value.length());
+    } else {
+      deleted = true;
+    }
+    if(deleted) {
+      value.clear();
+    }
+    return true;
+  }
+
+ private:
+  // ScanIterator does not report addresses. Records never span pages, so one that does
+  // not fit in the rest of a page is at the start of the next.
+  inline Address Locate(uint32_t size) {
+    Address address = next_;
+    if(address.offset() + size > S::hlog_t::kPageSize) {
+      address = Address{ address.page() + 1, 0 };
+    }
+    next_ = Address{ address.control() + size };
+    return address;
+  }
+
+  ScopedSession session_;
+  S& store_;
+  ScanIterator<S> iterator_;
+  Address next_;
+};
+
+inline uint8_t status_code(Status status) {
+  return static_cast<uint8_t>(status);
+}
+
+// Completes a read through its callback if FASTER fails it without reading a value
+template <class RC>
+uint8_t read(faster_t* store, RC& context, uint64_t monotonic_serial_number) {
+  auto callback = [](IAsyncContext* ctxt, Status result) {
+    CallbackContext<RC> context{ ctxt };
+    if(result != Status::Ok) {
+      context->Fail(result);
+    }
+  };
+  Status status = with_store(store, [&](auto& store) {
+    return store.Read(context, callback, monotonic_serial_number);
+  });
+  if(status == Status::Ok && !context.found()) {
+    status = Status::NotFound;
+  } else if(status != Status::Ok && status != Status::Pending) {
+    context.Fail(status);
+  }
+  return status_code(status);
+}
+
+template <class UC>
+uint8_t upsert(faster_t* store, UC& context, uint64_t monotonic_serial_number) {
+  auto callback = [](IAsyncContext* ctxt, Status result) {
+    CallbackContext<UC> context{ ctxt };
+  };
+  return status_code(with_store(store, [&](auto& store) {
+    return store.Upsert(context, callback, monotonic_serial_number);
+  }));
+}
+
+template <class MC>
+uint8_t rmw(faster_t* store, MC& context, uint64_t monotonic_serial_number) {
+  auto callback = [](IAsyncContext* ctxt, Status result) {
+    CallbackContext<MC> context{ ctxt };
+  };
+  return status_code(with_store(store, [&](auto& store) {
+    return store.Rmw(context, callback, monotonic_serial_number);
+  }));
+}
+
+// Releases the operation's context once it has finished, which is only when its callback
+// runs if it went pending
+template <class MC>
+uint8_t rmw_and_release(faster_t* store, MC& context, uint64_t monotonic_serial_number) {
+  auto callback = [](IAsyncContext* ctxt, Status result) {
+    CallbackContext<MC> context{ ctxt };
+    context->Release();
+  };
+  Status status = with_store(store, [&](auto& store) {
+    return store.Rmw(context, callback, monotonic_serial_number);
+  });
+  if(status != Status::Pending) {
+    context.Release();
+  }
+  return status_code(status);
+}
+
+template <class DC>
+uint8_t remove(faster_t* store, DC& context, uint64_t monotonic_serial_number) {
+  auto callback = [](IAsyncContext* ctxt, Status result) {
+    CallbackContext<DC> context{ ctxt };
+  };
+  return status_code(with_store(store, [&](auto& store) {
+    return store.Delete(context, callback, monotonic_serial_number);
+  }));
+}
+
+char* copy_string(const std::string& str) {
+  char* copy = static_cast<char*>(std::malloc(str.size() + 1));
+  std::memcpy(copy, str.c_str(), str.size() + 1);
+  return copy;
+}
+
+faster_checkpoint_result* checkpoint_result(bool checked, const Guid& token) {
+  faster_checkpoint_result* result =
+    static_cast<faster_checkpoint_result*>(std::malloc(sizeof(faster_checkpoint_result)));
+  result->checked = checked;
+  result->token = copy_string(token.ToString());
+  return result;
+}
+
+// Persistence is observed through the checkpoint's files rather than these callbacks
+void index_persisted(Status result) {
+}
+
+void hybrid_log_persisted(Status result, uint64_t persistent_serial_num) {
+}
+
+// FASTER's callback for a finished growth of the index takes no context. It is called from
+// within a call on the store whose index grew, so the caller's callback waits in that store.
+void index_grown(uint64_t new_size) {
+  PendingGrowth& growth = current_store->growth;
+  grow_callback cb;
+  void* context;
+  {
+    std::lock_guard<std::mutex> lock{ growth.mutex };
+    growth.growing = false;
+    cb = growth.cb;
+    context = growth.context;
+  }
+  if(cb != nullptr) {
+    cb(context, new_size);
+  }
+}
+
+// Calls visit(entries, overflow_buckets) for every bucket of the hash index, with the
+// entries in use across the bucket and the number of overflow buckets chained to it
+template <class S, class Visit>
+void visit_index(S& store, Visit&& visit) {
+  const auto& table = store.index_table();
+  std::vector<HashBucketEntry> entries;
+  for(uint64_t idx = 0; idx < table.size(); ++idx) {
+    entries.clear();
+    uint64_t overflow_buckets = 0;
+    const HashBucket* bucket = &table.bucket(idx);
+    while(true) {
+      for(uint32_t entry_idx = 0; entry_idx < HashBucket::kNumEntries; ++entry_idx) {
+        HashBucketEntry entry = bucket->entries[entry_idx].load();
+        if(!entry.unused()) {
+          entries.push_back(entry);
+        }
+      }
+      HashBucketOverflowEntry overflow_entry = bucket->overflow_entry.load();
+      if(overflow_entry.unused()) {
+        break;
+      }
+      bucket = &store.overflow_buckets().Get(overflow_entry.address());
+      ++overflow_buckets;
+    }
+    visit(entries, overflow_buckets);
+  }
+}
+
+// Counts the keys sharing an entry of the hash index beyond the first, following the
+// entry's chain of records while it is in memory
+template <class S>
+uint64_t count_tag_collisions(S& store, const HashBucketEntry& entry) {
+  std::vector<const Key*> keys;
+  Address head = store.hlog.head_address.load();
+  for(Address address = entry.address(); address >= head;) {
+    auto record = reinterpret_cast<const typename S::record_t*>(store.hlog.Get(address));
+    const Key& key = record->key();
+    if(std::none_of(keys.begin(), keys.end(), [&](const Key* other) {
+         return *other == key;
+       })) {
+      keys.push_back(&key);
+    }
+    address = record->header.previous_address();
+  }
+  return keys.empty() ? 0 : keys.size() - 1;
+}
+
+// Keeps the current value of a key, so that a Read-Modify-Write copies it to the tail of
+// the log, and leaves a missing key missing
+uint64_t keep_value(void* context, const uint8_t* current, uint64_t length_current,
+                    const uint8_t* modification, uint64_t length_modification, uint8_t* dst) {
+  return RMW_UNCHANGED;
+}
+
+void release_nothing(void* context) {
+}
+
+// The log's files are truncated in the background once every session has moved on
+void truncated(uint64_t offset) {
+}
+
+void shifted() {
+}
+
+// Number of records compaction copies between completing its pending copies
+constexpr uint64_t kCompactPendingInterval = 256;
+
+// Copies the live records before `until` to the tail of the log and moves the begin address
+// of the log to `until`. Called on a thread without a session.
+template <class S>
+bool compact(faster_t* faster, S& store, Address until) {
+  if(until < store.hlog.begin_address.load() || until > store.hlog.safe_read_only_address.load()) {
+    return false;
+  }
+  ScopedSession session{ faster };
+  Address tail = store.hlog.GetTailAddress();
+
+  // The keys whose latest record lies before `until`
+  std::unordered_set<std::string> live;
+  {
+    StoreScan<S> scan{ faster, store, store.hlog.begin_address.load(), until };
+    while(scan.Next()) {
+      std::string key(scan.key.begin(), scan.key.end());
+      if(scan.deleted) {
+        live.erase(key);
+      } else {
+        live.insert(key);
+      }
+    }
+  }
+  {
+    StoreScan<S> scan{ faster, store, until, tail };
+    while(scan.Next()) {
+      live.erase(std::string(scan.key.begin(), scan.key.end()));
+    }
+  }
+
+  // A Read-Modify-Write copies the latest value rather than the one scanned, so a key
+  // updated or deleted since is not reverted
+  uint64_t serial_number = 0;
+  for(const std::string& key : live) {
+    ContextRmwContext context{ reinterpret_cast<const uint8_t*>(key.data()), key.size(),
+                               nullptr, 0, true, keep_value, nullptr, release_nothing };
+    rmw_and_release(faster, context, ++serial_number);
+    if(serial_number % kCompactPendingInterval == 0) {
+      store.CompletePending(false);
+    }
+  }
+  store.CompletePending(true);
+  return store.ShiftBeginAddress(until, truncated, shifted);
+}
+
+}  // namespace
+
+extern "C" {
+
+  faster_t* faster_open(const uint64_t table_size, const uint64_t log_size,
+                        const bool pre_allocate_log) {
+    faster_t* res = new faster_t{};
+    res->memory_store = new memory_store_t{ table_size, log_size, "", 0.9, pre_allocate_log };
+    return res;
+  }
+
+  faster_t* faster_open_with_disk(const uint64_t table_size, const uint64_t log_size,
+                                  const char* storage, const double log_mutable_fraction,
+                                  const bool pre_allocate_log) {
+    faster_t* res = new faster_t{};
+    res->disk_store = new disk_store_t{ table_size, log_size, storage, log_mutable_fraction,
+                                        pre_allocate_log };
+    return res;
+  }
+
+  uint8_t faster_upsert(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                        uint8_t* value, const uint64_t value_length,
+                        const uint64_t monotonic_serial_number) {
+    UpsertContext context{ key, key_length, value, value_length };
+    uint8_t status = upsert(faster_t, context, monotonic_serial_number);
+    deallocate_vec(key, key_length);
+    deallocate_vec(value, value_length);
+    return status;
+  }
+
+  uint8_t faster_read(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                      const uint64_t monotonic_serial_number, read_callback cb, void* target) {
+    ReadContext context{ key, key_length, cb, target };
+    uint8_t status = read(faster_t, context, monotonic_serial_number);
+    deallocate_vec(key, key_length);
+    return status;
+  }
+
+  uint8_t faster_rmw(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                     uint8_t* modification, const uint64_t length,
+                     const uint64_t monotonic_serial_number, rmw_initial_callback initial,
+                     rmw_in_place_callback in_place, rmw_callback cb) {
+    RmwContext context{ key, key_length, modification, length, initial, in_place, cb };
+    uint8_t status = rmw(faster_t, context, monotonic_serial_number);
+    deallocate_vec(key, key_length);
+    deallocate_vec(modification, length);
+    return status;
+  }
+
+  uint8_t faster_rmw_borrowed(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                              const uint8_t* modification, const uint64_t length,
+                              const uint64_t monotonic_serial_number,
+                              rmw_initial_callback initial, rmw_in_place_callback in_place,
+                              rmw_callback cb) {
+    RmwContext context{ key, key_length, modification, length, initial, in_place, cb };
+    return rmw(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_delete(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                        const uint64_t monotonic_serial_number) {
+    DeleteContext context{ key, key_length };
+    uint8_t status = remove(faster_t, context, monotonic_serial_number);
+    deallocate_vec(key, key_length);
+    return status;
+  }
+
+  uint8_t faster_upsert_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                              const uint8_t* value, const uint64_t value_length,
+                              const uint64_t monotonic_serial_number) {
+    UpsertContext context{ key, key_length, value, value_length };
+    return upsert(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_read_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint64_t monotonic_serial_number, read_callback cb,
+                            void* target) {
+    ReadContext context{ key, key_length, cb, target };
+    return read(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_rmw_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                           const uint8_t* modification, const uint64_t length,
+                           const uint64_t monotonic_serial_number, rmw_context_callback cb,
+                           void* context, release_callback release) {
+    ContextRmwContext rmw_context{ key, key_length, modification, length, false,
+                                   cb, context, release };
+    return rmw_and_release(faster_t, rmw_context, monotonic_serial_number);
+  }
+
+  uint8_t faster_rmw_with(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                          const uint8_t* input, const uint64_t input_length,
+                          const uint64_t monotonic_serial_number, rmw_context_callback cb,
+                          void* context, release_callback release) {
+    ContextRmwContext rmw_context{ key, key_length, input, input_length, true,
+                                   cb, context, release };
+    return rmw_and_release(faster_t, rmw_context, monotonic_serial_number);
+  }
+
+  uint8_t faster_upsert_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint8_t* value, const uint64_t value_length,
+                            const uint64_t monotonic_serial_number) {
+    UpsertContext context{ key, key_length, value, value_length };
+    return upsert(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_read_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                          const uint64_t value_length, const uint64_t monotonic_serial_number,
+                          read_callback cb, void* target) {
+    ReadContext context{ key, key_length, cb, target };
+    return read(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_rmw_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                         const uint8_t* modification, const uint64_t value_length,
+                         const uint64_t monotonic_serial_number,
+                         rmw_pod_initial_callback initial, rmw_pod_callback cb) {
+    PodRmwContext context{ key, key_length, modification, value_length, initial, cb };
+    return rmw(faster_t, context, monotonic_serial_number);
+  }
+
+  uint8_t faster_delete_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint64_t monotonic_serial_number) {
+    DeleteContext context{ key, key_length };
+    return remove(faster_t, context, monotonic_serial_number);
+  }
+
+  uint64_t faster_size(faster_t* faster_t) {
+    return with_store(faster_t, [](auto& store) {
+      return store.Size();
+    });
+  }
+
+  faster_checkpoint_result* faster_checkpoint(faster_t* faster_t) {
+    Guid token;
+    bool checked = with_store(faster_t, [&](auto& store) {
+      return store.Checkpoint(index_persisted, hybrid_log_persisted, token);
+    });
+    return checkpoint_result(checked, token);
+  }
+
+  faster_checkpoint_result* faster_checkpoint_index(faster_t* faster_t) {
+    Guid token;
+    bool checked = with_store(faster_t, [&](auto& store) {
+      return store.CheckpointIndex(index_persisted, token);
+    });
+    return checkpoint_result(checked, token);
+  }
+
+  faster_checkpoint_result* faster_checkpoint_hybrid_log(faster_t* faster_t) {
+    Guid token;
+    bool checked = with_store(faster_t, [&](auto& store) {
+      return store.CheckpointHybridLog(hybrid_log_persisted, token);
+    });
+    return checkpoint_result(checked, token);
+  }
+
+  faster_recover_result* faster_recover(faster_t* faster_t, const char* index_token,
+                                        const char* hybrid_log_token) {
+    uint32_t version = 0;
+    std::vector<Guid> session_ids;
+    Status status = with_store(faster_t, [&](auto& store) {
+      return store.Recover(Guid::Parse(index_token), Guid::Parse(hybrid_log_token), version,
+                           session_ids);
+    });
+
+    faster_recover_result* result =
+      static_cast<faster_recover_result*>(std::calloc(1, sizeof(faster_recover_result)));
+    result->status = status_code(status);
+    result->version = version;
+    result->session_ids_count = static_cast<int>(session_ids.size());
+    result->session_ids = static_cast<char*>(std::malloc(37 * session_ids.size()));
+    for(size_t idx = 0; idx < session_ids.size(); ++idx) {
+      std::strncpy(result->session_ids + 37 * idx, session_ids[idx].ToString().c_str(), 37);
+    }
+    return result;
+  }
+
+  void faster_checkpoint_result_free(faster_checkpoint_result* result) {
+    std::free(result->token);
+    std::free(result);
+  }
+
+  void faster_recover_result_free(faster_recover_result* result) {
+    std::free(result->session_ids);
+    std::free(result);
+  }
+
+  bool faster_complete_pending(faster_t* faster_t, bool wait) {
+    return with_store(faster_t, [&](auto& store) {
+      return store.CompletePending(wait);
+    });
+  }
+
+  const char* faster_start_session(faster_t* faster_t) {
+    static thread_local std::string guid;
+    guid = with_store(faster_t, [](auto& store) {
+      return store.StartSession();
+    }).ToString();
+    thread_sessions.push_back(faster_t);
+    return guid.c_str();
+  }
+
+  uint64_t faster_continue_session(faster_t* faster_t, const char* token) {
+    uint64_t serial_number = with_store(faster_t, [&](auto& store) {
+      return store.ContinueSession(Guid::Parse(token));
+    });
+    thread_sessions.push_back(faster_t);
+    return serial_number;
+  }
+
+  void faster_stop_session(faster_t* faster_t) {
+    with_store(faster_t, [](auto& store) {
+      store.StopSession();
+    });
+    auto session = std::find(thread_sessions.begin(), thread_sessions.end(), faster_t);
+    if(session != thread_sessions.end()) {
+      thread_sessions.erase(session);
+    }
+  }
+
+  void faster_refresh_session(faster_t* faster_t) {
+    with_store(faster_t, [](auto& store) {
+      store.Refresh();
+    });
+  }
+
+  uint64_t faster_log_begin_address(faster_t* faster_t) {
+    return with_store(faster_t, [](auto& store) {
+      return store.hlog.begin_address.load().control();
+    });
+  }
+
+  uint64_t faster_log_tail_address(faster_t* faster_t) {
+    return with_store(faster_t, [](auto& store) {
+      return store.hlog.GetTailAddress().control();
+    });
+  }
+
+  faster_scan_t* faster_scan_begin(faster_t* faster_t, const uint64_t from, const uint64_t until) {
+    return with_store(faster_t, [&](auto& store) -> faster_scan_t* {
+      typedef typename std::remove_reference<decltype(store)>::type store_t;
+      uint64_t begin = std::max(from, store.hlog.begin_address.load().control());
+      uint64_t end = std::max(begin, std::min(until, store.hlog.GetTailAddress().control()));
+      return new StoreScan<store_t>{ faster_t, store, Address{ begin }, Address{ end } };
+    });
+  }
+
+  bool faster_scan_next(faster_scan_t* scan, const uint8_t** key, uint64_t* key_length,
+                        const uint8_t** value, uint64_t* value_length, uint64_t* address,
+                        bool* deleted) {
+    if(!scan->Next()) {
+      return false;
+    }
+    *key = scan->key.data();
+    *key_length = scan->key.size();
+    *value = scan->value.data();
+    *value_length = scan->value.size();
+    *address = scan->address;
+    *deleted = scan->deleted;
+    return true;
+  }
+
+  void faster_scan_end(faster_scan_t* scan) {
+    delete scan;
+  }
+
+  uint64_t faster_log_safe_read_only_address(faster_t* faster_t) {
+    return with_store(faster_t, [](auto& store) {
+      return store.hlog.safe_read_only_address.load().control();
+    });
+  }
+
+  bool faster_compact(faster_t* faster_t, const uint64_t until) {
+    // Compaction issues operations of its own, which must not be numbered as part of the
+    // calling thread's session, so it runs on a thread of its own
+    bool compacted = false;
+    std::thread worker{ [&]() {
+      compacted = with_store(faster_t, [&](auto& store) {
+        return compact(faster_t, store, Address{ until });
+      });
+    } };
+    worker.join();
+    return compacted;
+  }
+
+  void faster_get_log_stats(faster_t* faster_t, faster_log_stats* stats) {
+    with_store(faster_t, [&](auto& store) {
+      stats->begin_address = store.hlog.begin_address.load().control();
+      stats->head_address = store.hlog.head_address.load().control();
+      stats->safe_read_only_address = store.hlog.safe_read_only_address.load().control();
+      stats->read_only_address = store.hlog.read_only_address.load().control();
+      stats->tail_address = store.hlog.GetTailAddress().control();
+      stats->table_size = store.index_table().size();
+      stats->used_buckets = 0;
+      stats->entries = 0;
+      stats->overflow_buckets = 0;
+      visit_index(store, [&](const std::vector<HashBucketEntry>& entries,
+                             uint64_t overflow_buckets) {
+        stats->used_buckets += entries.empty() ? 0 : 1;
+        stats->entries += entries.size();
+        stats->overflow_buckets += overflow_buckets;
+      });
+    });
+  }
+
+  void faster_get_index_distribution(faster_t* faster_t,
+                                     faster_index_distribution* distribution) {
+    // Records are only read while this thread is protected from their pages being evicted
+    ScopedSession session{ faster_t };
+    with_store(faster_t, [&](auto& store) {
+      const uint64_t last = FASTER_INDEX_HISTOGRAM_SIZE - 1;
+      distribution->table_size = store.index_table().size();
+      distribution->entries = 0;
+      distribution->tag_collisions = 0;
+      std::fill_n(distribution->histogram, FASTER_INDEX_HISTOGRAM_SIZE, 0);
+      visit_index(store, [&](const std::vector<HashBucketEntry>& entries,
+                             uint64_t overflow_buckets) {
+        distribution->entries += entries.size();
+        ++distribution->histogram[std::min<uint64_t>(entries.size(), last)];
+        for(const HashBucketEntry& entry : entries) {
+          distribution->tag_collisions += count_tag_collisions(store, entry);
+        }
+      });
+    });
+  }
+
+  void faster_dump_distribution(faster_t* faster_t) {
+    with_store(faster_t, [](auto& store) {
+      store.DumpDistribution();
+    });
+  }
+
+  bool faster_grow_index(faster_t* faster_t) {
+    return faster_grow_index_with_callback(faster_t, nullptr, nullptr, nullptr);
+  }
+
+  bool faster_grow_index_with_callback(faster_t* faster_t, grow_callback cb, void* context,
+                                       release_callback release) {
+    PendingGrowth& growth = faster_t->growth;
+    {
+      std::lock_guard<std::mutex> lock{ growth.mutex };
+      if(growth.growing) {
+        return false;
+      }
+      growth.growing = true;
+      growth.cb = cb;
+      growth.context = context;
+      growth.release = release;
+    }
+    // The growth may finish before GrowIndex returns, so the lock is not held meanwhile
+    bool growing = with_store(faster_t, [](auto& store) {
+      return store.GrowIndex(index_grown);
+    });
+    if(!growing) {
+      std::lock_guard<std::mutex> lock{ growth.mutex };
+      growth.growing = false;
+    }
+    return growing;
+  }
+
+  void faster_destroy(faster_t* faster_t) {
+    // A growth that had not finished never will, so its context is handed back
+    release_callback release = nullptr;
+    void* context = nullptr;
+    {
+      std::lock_guard<std::mutex> lock{ faster_t->growth.mutex };
+      if(faster_t->growth.growing) {
+        release = faster_t->growth.release;
+        context = faster_t->growth.context;
+      }
+    }
+    delete faster_t->memory_store;
+    delete faster_t->disk_store;
+    delete faster_t;
+    if(release != nullptr) {
+      release(context);
+    }
+  }
+
+}  // extern "C"
@@ -0,0 +1,265 @@
+// C interface to FASTER's key-value store, for use from Rust through bindgen.
+//
+// Keys and values are opaque byte strings. Unless stated otherwise, operations must be
+// issued from a thread with an active session and return a faster_status code.
+
+#ifndef FASTER_C_H_
+#define FASTER_C_H_
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+  typedef struct faster_t faster_t;
+  typedef struct faster_checkpoint_result faster_checkpoint_result;
+  typedef struct faster_recover_result faster_recover_result;
+  typedef struct faster_scan_t faster_scan_t;
+  typedef struct faster_log_stats faster_log_stats;
+  typedef struct faster_index_distribution faster_index_distribution;
+
+  enum faster_status {
+    Ok,
+    Pending,
+    NotFound,
+    OutOfMemory,
+    IOError,
+    Corruption,
+    Aborted,
+  };
+  typedef enum faster_status faster_status;
+
+  // Receives the value of a read, or a null value with the status the read failed with.
+  // Called exactly once per read, either before the read returns or from
+  // faster_complete_pending if it went pending. The value is only valid during the call.
+  typedef void (*read_callback)(void* target, const uint8_t* value, uint64_t length,
+                                faster_status status);
+
+  // Writes the updated value to dst and returns its length. When dst is null only the
+  // length is returned. Called more than once per update, so it must be deterministic.
+  typedef uint64_t (*rmw_callback)(const uint8_t* current, uint64_t length_current,
+                                   uint8_t* modification, uint64_t length_modification,
+                                   uint8_t* dst);
+
+  // Like rmw_callback, for the value of a missing key
+  typedef uint64_t (*rmw_initial_callback)(const uint8_t* modification,
+                                           uint64_t length_modification, uint8_t* dst);
+
+  // Updates the current value where it is stored, without changing its length. Returns
+  // false if it cannot, in which case rmw_callback writes the update to a new record.
+  // Called while the value is locked against other updates and reads.
+  typedef bool (*rmw_in_place_callback)(uint8_t* current, uint64_t length_current,
+                                        const uint8_t* modification,
+                                        uint64_t length_modification);
+
+  // Returned by an rmw_context_callback to keep the current value, or not to create a
+  // missing key
+#define RMW_UNCHANGED UINT64_MAX
+
+  // Like rmw_callback, for a callback that needs the context passed along with it.
+  // faster_rmw_with passes a null current value for a missing key.
+  typedef uint64_t (*rmw_context_callback)(void* context, const uint8_t* current,
+                                           uint64_t length_current,
+                                           const uint8_t* modification,
+                                           uint64_t length_modification, uint8_t* dst);
+
+  // Hands a callback's context back to its owner, once the callback will not be called again
+  typedef void (*release_callback)(void* context);
+
+  // Writes the update of a fixed-size value to dst, which never overlaps current
+  typedef void (*rmw_pod_callback)(const uint8_t* current, const uint8_t* modification,
+                                   uint8_t* dst);
+  // Writes the value of a missing key to dst
+  typedef void (*rmw_pod_initial_callback)(const uint8_t* modification, uint8_t* dst);
+
+  // Receives the number of buckets of the hash index once it has grown
+  typedef void (*grow_callback)(void* context, uint64_t new_size);
+
+  struct faster_checkpoint_result {
+    bool checked;
+    char* token;
+  };
+
+  struct faster_recover_result {
+    uint8_t status;
+    uint32_t version;
+    int session_ids_count;
+    // session_ids_count NUL-terminated GUIDs of 37 bytes each
+    char* session_ids;
+  };
+
+  struct faster_log_stats {
+    // Addresses dividing the hybrid log into its regions, in increasing order
+    uint64_t begin_address;
+    uint64_t head_address;
+    uint64_t safe_read_only_address;
+    uint64_t read_only_address;
+    uint64_t tail_address;
+    // Buckets of the main hash table, and those of them holding at least one entry
+    uint64_t table_size;
+    uint64_t used_buckets;
+    // Entries across all buckets, including overflow buckets
+    uint64_t entries;
+    uint64_t overflow_buckets;
+  };
+
+  // Bucket sizes in a histogram of the index: 0 to 14 entries, then 15 or more
+#define FASTER_INDEX_HISTOGRAM_SIZE 16
+
+  struct faster_index_distribution {
+    uint64_t table_size;
+    uint64_t entries;
+    // Keys sharing an entry, and so a tag, with another key, beyond the first. Only the
+    // records still in memory are compared.
+    uint64_t tag_collisions;
+    // histogram[i] counts the buckets whose entries, including those of their overflow
+    // buckets, number i. The last element counts every bucket with more entries.
+    uint64_t histogram[FASTER_INDEX_HISTOGRAM_SIZE];
+  };
+
+  // Thread-related operations
+
+  // The returned GUID is only valid until the next call on this thread
+  const char* faster_start_session(faster_t* faster_t);
+  uint64_t faster_continue_session(faster_t* faster_t, const char* token);
+  void faster_stop_session(faster_t* faster_t);
+  void faster_refresh_session(faster_t* faster_t);
+  // Completes the operations of this thread's session that went pending, waiting for
+  // them all if `wait` is set. Returns true if none of them remain pending.
+  bool faster_complete_pending(faster_t* faster_t, bool wait);
+
+  // Checkpoint/Recover
+  faster_checkpoint_result* faster_checkpoint(faster_t* faster_t);
+  // Checkpoints of only the hash index or only the hybrid log. Recovery needs one of each.
+  faster_checkpoint_result* faster_checkpoint_index(faster_t* faster_t);
+  faster_checkpoint_result* faster_checkpoint_hybrid_log(faster_t* faster_t);
+  faster_recover_result* faster_recover(faster_t* faster_t, const char* index_token,
+                                        const char* hybrid_log_token);
+  // Free a result along with the strings it holds
+  void faster_checkpoint_result_free(faster_checkpoint_result* result);
+  void faster_recover_result_free(faster_recover_result* result);
+
+  // Operations
+
+  faster_t* faster_open(const uint64_t table_size, const uint64_t log_size,
+                        const bool pre_allocate_log);
+  faster_t* faster_open_with_disk(const uint64_t table_size, const uint64_t log_size,
+                                  const char* storage, const double log_mutable_fraction,
+                                  const bool pre_allocate_log);
+
+  // faster_upsert, faster_read, faster_rmw and faster_delete take ownership of the key
+  // and value buffers, which are released with the deallocate_vec function exported by
+  // the Rust crate
+  uint8_t faster_upsert(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                        uint8_t* value, const uint64_t value_length,
+                        const uint64_t monotonic_serial_number);
+  uint8_t faster_read(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                      const uint64_t monotonic_serial_number, read_callback cb, void* target);
+  uint8_t faster_rmw(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                     uint8_t* modification, const uint64_t length,
+                     const uint64_t monotonic_serial_number, rmw_initial_callback initial,
+                     rmw_in_place_callback in_place, rmw_callback cb);
+  // Returns NotFound if the key does not exist
+  uint8_t faster_delete(faster_t* faster_t, uint8_t* key, const uint64_t key_length,
+                        const uint64_t monotonic_serial_number);
+
+  // The _bytes operations borrow the key, value and modification for the duration of the
+  // call and copy them if the operation goes pending. faster_rmw_bytes only calls cb if
+  // the key exists, and creates a missing key with the modification as its value. It
+  // calls release(context) exactly once, when the operation has finished: before
+  // returning, or from faster_complete_pending if it returned Pending.
+  uint8_t faster_upsert_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                              const uint8_t* value, const uint64_t value_length,
+                              const uint64_t monotonic_serial_number);
+  uint8_t faster_read_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint64_t monotonic_serial_number, read_callback cb,
+                            void* target);
+  uint8_t faster_rmw_bytes(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                           const uint8_t* modification, const uint64_t length,
+                           const uint64_t monotonic_serial_number, rmw_context_callback cb,
+                           void* context, release_callback release);
+  // Like faster_rmw, over a borrowed key and modification
+  uint8_t faster_rmw_borrowed(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                              const uint8_t* modification, const uint64_t length,
+                              const uint64_t monotonic_serial_number,
+                              rmw_initial_callback initial, rmw_in_place_callback in_place,
+                              rmw_callback cb);
+  // Like faster_rmw_bytes, but a missing key is passed to cb, which may return
+  // RMW_UNCHANGED to leave the key missing
+  uint8_t faster_rmw_with(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                          const uint8_t* input, const uint64_t input_length,
+                          const uint64_t monotonic_serial_number, rmw_context_callback cb,
+                          void* context, release_callback release);
+
+  // The _pod operations borrow fixed-size keys and values, which they store without any
+  // encoding. Their records share the key space of the other operations. value_length is
+  // the size of the caller's value type. faster_rmw_pod always writes the update to a
+  // new record.
+  uint8_t faster_upsert_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint8_t* value, const uint64_t value_length,
+                            const uint64_t monotonic_serial_number);
+  uint8_t faster_read_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                          const uint64_t value_length, const uint64_t monotonic_serial_number,
+                          read_callback cb, void* target);
+  uint8_t faster_rmw_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                         const uint8_t* modification, const uint64_t value_length,
+                         const uint64_t monotonic_serial_number,
+                         rmw_pod_initial_callback initial, rmw_pod_callback cb);
+  uint8_t faster_delete_pod(faster_t* faster_t, const uint8_t* key, const uint64_t key_length,
+                            const uint64_t monotonic_serial_number);
+
+  // Statistics; these do not need a session
+  uint64_t faster_size(faster_t* faster_t);
+  void faster_dump_distribution(faster_t* faster_t);
+  // Addresses of the oldest record in the log and of the end of the log
+  uint64_t faster_log_begin_address(faster_t* faster_t);
+  uint64_t faster_log_tail_address(faster_t* faster_t);
+  // Records before this address are no longer updated in place
+  uint64_t faster_log_safe_read_only_address(faster_t* faster_t);
+  // Taken without stopping other threads, so the fields may not be consistent with each other
+  void faster_get_log_stats(faster_t* faster_t, faster_log_stats* stats);
+  // Walks the whole hash index and the records of its entries that are in memory
+  void faster_get_index_distribution(faster_t* faster_t,
+                                     faster_index_distribution* distribution);
+
+  // Scans
+  //
+  // A scan visits the records of the log between two addresses, oldest first, including
+  // the versions of a key that have since been superseded. Addresses outside the log are
+  // clamped to it. A thread without a session with the store is given one until
+  // faster_scan_end, so a scan must begin, advance and end on the same thread.
+
+  faster_scan_t* faster_scan_begin(faster_t* faster_t, const uint64_t from, const uint64_t until);
+  // Moves to the next record, returning false once there are none left. A deleted record
+  // has an empty value. The key and value are only valid until the next call on the scan.
+  bool faster_scan_next(faster_scan_t* scan, const uint8_t** key, uint64_t* key_length,
+                        const uint8_t** value, uint64_t* value_length, uint64_t* address,
+                        bool* deleted);
+  void faster_scan_end(faster_scan_t* scan);
+
+  // Maintenance; these do not need a session
+
+  // Starts doubling the hash index, returning false if FASTER refused to, e.g. during a
+  // checkpoint, or if the index is already growing. The index grows as the threads with a
+  // session refresh, and once it has, cb(context, new_size) is called from the thread that
+  // finished the growth. If the store is destroyed first, release(context) is called
+  // instead. Neither is called if it returns false.
+  bool faster_grow_index_with_callback(faster_t* faster_t, grow_callback cb, void* context,
+                                       release_callback release);
+  // Like faster_grow_index_with_callback, without being told when the index has grown
+  bool faster_grow_index(faster_t* faster_t);
+  // Copies the live records before `until` to the tail of the log and moves the begin
+  // address of the log to `until`. Keys updated or deleted while compacting keep their
+  // newer state. Returns false if `until` lies before the begin address or after the safe
+  // read-only address.
+  bool faster_compact(faster_t* faster_t, const uint64_t until);
+  void faster_destroy(faster_t* faster_t);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FASTER_C_H_ */
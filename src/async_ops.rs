@@ -0,0 +1,320 @@
+use crate::status::Status;
+use crate::{BatchResult, Bincode, Codec, FasterError, ReadResult, RmwFetchResult, Session};
+
+use futures::channel::oneshot;
+use std::cmp;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Delays between polls of a future whose operation is still pending
+const MIN_POLL_DELAY: Duration = Duration::from_micros(20);
+const MAX_POLL_DELAY: Duration = Duration::from_millis(1);
+
+fn check_status(status: u8) -> Result<Status, FasterError<'static>> {
+    Status::try_from(status)?.into_result()
+}
+
+// Nothing wakes a future whose operation went pending, as FASTER only completes it when
+// the session's thread calls complete_pending. Instead of waking itself up right away,
+// which keeps a core busy, the future asks to be woken after a delay that doubles while
+// the operation remains pending.
+struct Backoff {
+    delay: Duration,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Backoff {
+            delay: MIN_POLL_DELAY,
+        }
+    }
+
+    fn wake_later(&mut self, waker: &Waker) {
+        wake_after(self.delay, waker.clone());
+        self.delay = cmp::min(self.delay * 2, MAX_POLL_DELAY);
+    }
+}
+
+// Wakes the waker once `delay` has passed, from a timer thread shared by all futures
+fn wake_after(delay: Duration, waker: Waker) {
+    static TIMER: OnceLock<Mutex<Sender<(Instant, Waker)>>> = OnceLock::new();
+    let timer = TIMER.get_or_init(|| {
+        let (sender, receiver) = channel();
+        thread::Builder::new()
+            .name(String::from("faster-async-timer"))
+            .spawn(move || run_timer(receiver))
+            .expect("unable to start the timer thread of the async operations");
+        Mutex::new(sender)
+    });
+    // The timer thread never exits, so sending cannot fail
+    let _ = timer.lock().unwrap().send((Instant::now() + delay, waker));
+}
+
+fn run_timer(receiver: Receiver<(Instant, Waker)>) {
+    let mut wakers: Vec<(Instant, Waker)> = Vec::new();
+    loop {
+        let now = Instant::now();
+        wakers.retain(|(deadline, waker)| {
+            if *deadline <= now {
+                waker.wake_by_ref();
+                false
+            } else {
+                true
+            }
+        });
+        let received = match wakers.iter().map(|(deadline, _)| *deadline).min() {
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(deadline) => receiver.recv_timeout(deadline - now),
+        };
+        match received {
+            Ok(entry) => wakers.push(entry),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Resolves to the value of a read, or `None` if the key does not exist.
+///
+/// FASTER only completes the operations that went pending, such as reads that go to
+/// disk, when the thread that issued them calls `complete_pending`. Polling the future
+/// does so without waiting. While the read is still in flight, the future has a shared
+/// timer thread wake it up to be polled again, after 20 µs at first and doubling up to
+/// 1 ms. A pending read therefore resolves up to that long after FASTER finished it,
+/// in exchange for not keeping a core busy. Use the blocking
+/// `Session::complete_pending(true)` when the latency matters more.
+///
+/// The future borrows the `Session` that issued the operation and is bound to its
+/// thread, so it is not `Send`. It must be driven on that thread, e.g. with
+/// `futures::executor::block_on` or `tokio::task::spawn_local` in a `LocalSet`,
+/// rather than `tokio::spawn`. The same holds for the other futures of a session.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<faster_rs::ReadFuture<u64>>();
+/// ```
+pub struct ReadFuture<'s, 'a, V, C: Codec = Bincode> {
+    session: &'s Session<'a, C>,
+    status: u8,
+    receiver: oneshot::Receiver<ReadResult<V>>,
+    backoff: Backoff,
+}
+
+impl<'s, 'a, V, C: Codec> ReadFuture<'s, 'a, V, C> {
+    pub(crate) fn new(
+        session: &'s Session<'a, C>,
+        status: u8,
+        receiver: oneshot::Receiver<ReadResult<V>>,
+    ) -> Self {
+        ReadFuture {
+            session,
+            status,
+            receiver,
+            backoff: Backoff::new(),
+        }
+    }
+}
+
+impl<'s, 'a, V, C: Codec> Future for ReadFuture<'s, 'a, V, C> {
+    type Output = Result<Option<V>, FasterError<'static>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match check_status(self.status) {
+            Err(err) => return Poll::Ready(Err(err)),
+            Ok(Status::NotFound) => return Poll::Ready(Ok(None)),
+            Ok(_) => {}
+        }
+
+        let this = &mut *self;
+        poll_receiver(this.session, &mut this.receiver, &mut this.backoff, cx)
+    }
+}
+
+// Polls for the result of an operation, completing the session's pending operations if needed
+fn poll_receiver<T, C: Codec>(
+    session: &Session<C>,
+    receiver: &mut oneshot::Receiver<Result<T, FasterError<'static>>>,
+    backoff: &mut Backoff,
+    cx: &mut Context,
+) -> Poll<Result<T, FasterError<'static>>> {
+    let mut polled = Pin::new(&mut *receiver).poll(cx);
+    if polled.is_pending() {
+        session.complete_pending(false);
+        polled = Pin::new(&mut *receiver).poll(cx);
+    }
+    match polled {
+        Poll::Ready(Ok(result)) => Poll::Ready(result),
+        // The callback dropped the sender without delivering a result
+        Poll::Ready(Err(oneshot::Canceled)) => {
+            Poll::Ready(Err(FasterError::OperationError(Status::Aborted)))
+        }
+        Poll::Pending => {
+            backoff.wake_later(cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+/// Resolves to the values before and after a Read-Modify-Write. Polled like `ReadFuture`
+/// while the update is pending.
+pub struct RmwFetchFuture<'s, 'a, V, C: Codec = Bincode> {
+    session: &'s Session<'a, C>,
+    status: u8,
+    receiver: oneshot::Receiver<RmwFetchResult<V>>,
+    backoff: Backoff,
+}
+
+impl<'s, 'a, V, C: Codec> RmwFetchFuture<'s, 'a, V, C> {
+    pub(crate) fn new(
+        session: &'s Session<'a, C>,
+        status: u8,
+        receiver: oneshot::Receiver<RmwFetchResult<V>>,
+    ) -> Self {
+        RmwFetchFuture {
+            session,
+            status,
+            receiver,
+            backoff: Backoff::new(),
+        }
+    }
+}
+
+impl<'s, 'a, V, C: Codec> Future for RmwFetchFuture<'s, 'a, V, C> {
+    type Output = RmwFetchResult<V>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if let Err(err) = check_status(self.status) {
+            return Poll::Ready(Err(err));
+        }
+        let this = &mut *self;
+        poll_receiver(this.session, &mut this.receiver, &mut this.backoff, cx)
+    }
+}
+
+/// Resolves to the values of a batch of reads, in the order the keys were given. Polled
+/// like `ReadFuture` while some of the reads are pending.
+pub struct ReadBatchFuture<'s, 'a, V, C: Codec = Bincode> {
+    session: &'s Session<'a, C>,
+    receiver: Receiver<(usize, ReadResult<V>)>,
+    results: Vec<Option<ReadResult<V>>>,
+    remaining: usize,
+    backoff: Backoff,
+}
+
+impl<'s, 'a, V, C: Codec> ReadBatchFuture<'s, 'a, V, C> {
+    pub(crate) fn new(
+        session: &'s Session<'a, C>,
+        statuses: Vec<BatchResult>,
+        receiver: Receiver<(usize, ReadResult<V>)>,
+    ) -> Self {
+        // Reads that did not find their key or failed outright are already decided
+        let results: Vec<Option<ReadResult<V>>> = statuses
+            .into_iter()
+            .map(|status| match status.and_then(check_status) {
+                Err(err) => Some(Err(err)),
+                Ok(Status::NotFound) => Some(Ok(None)),
+                Ok(_) => None,
+            })
+            .collect();
+        let remaining = results.iter().filter(|result| result.is_none()).count();
+        ReadBatchFuture {
+            session,
+            receiver,
+            results,
+            remaining,
+            backoff: Backoff::new(),
+        }
+    }
+
+    // Returns true once the channel has been closed
+    fn receive(&mut self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok((index, result)) => {
+                    if self.results[index].is_none() {
+                        self.results[index] = Some(result);
+                        self.remaining -= 1;
+                    }
+                }
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => return true,
+            }
+        }
+    }
+}
+
+// No field is structurally pinned
+impl<'s, 'a, V, C: Codec> Unpin for ReadBatchFuture<'s, 'a, V, C> {}
+
+impl<'s, 'a, V, C: Codec> Future for ReadBatchFuture<'s, 'a, V, C> {
+    type Output = Vec<ReadResult<V>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut closed = this.receive();
+        if this.remaining > 0 && !closed {
+            this.session.complete_pending(false);
+            closed = this.receive();
+        }
+        if this.remaining > 0 && !closed {
+            this.backoff.wake_later(cx.waker());
+            return Poll::Pending;
+        }
+        // Callbacks dropped without delivering a result are reported as aborted
+        let results = this
+            .results
+            .drain(..)
+            .map(|result| {
+                result.unwrap_or_else(|| Err(FasterError::OperationError(Status::Aborted)))
+            })
+            .collect();
+        Poll::Ready(results)
+    }
+}
+
+/// Resolves once an Upsert or RMW has been applied to the store.
+///
+/// FASTER does not report when an individual pending operation finishes, so a
+/// pending operation resolves once none of the session's operations remain pending.
+/// Like `ReadFuture`, polling completes what it can without blocking, and until then
+/// the future is woken up again after a growing delay.
+pub struct CompletionFuture<'s, 'a, C: Codec = Bincode> {
+    session: &'s Session<'a, C>,
+    status: u8,
+    backoff: Backoff,
+}
+
+impl<'s, 'a, C: Codec> CompletionFuture<'s, 'a, C> {
+    pub(crate) fn new(session: &'s Session<'a, C>, status: u8) -> Self {
+        CompletionFuture {
+            session,
+            status,
+            backoff: Backoff::new(),
+        }
+    }
+}
+
+impl<'s, 'a, C: Codec> Future for CompletionFuture<'s, 'a, C> {
+    type Output = Result<(), FasterError<'static>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match check_status(self.status) {
+            Err(err) => Poll::Ready(Err(err)),
+            Ok(Status::Pending) => {
+                if self.session.try_complete_pending() {
+                    Poll::Ready(Ok(()))
+                } else {
+                    self.backoff.wake_later(cx.waker());
+                    Poll::Pending
+                }
+            }
+            Ok(_) => Poll::Ready(Ok(())),
+        }
+    }
+}
@@ -1,5 +1,14 @@
-use crate::{FasterError, FasterKv};
+use crate::checkpoint_scheduler::CheckpointScheduler;
+use crate::compaction::CompactionScheduler;
+use crate::index_growth::IndexGrowthScheduler;
+use crate::status;
+use crate::util::Recover;
+use crate::{
+    CheckpointCatalog, CheckpointSchedule, Codec, CompactionPolicy, FasterError, FasterKey,
+    FasterKv, FasterValue, IndexGrowthPolicy, TypedFasterKv,
+};
 use std::ffi::CString;
+use std::marker::PhantomData;
 
 pub struct FasterKvBuilder<'a> {
     table_size: u64,
@@ -7,6 +16,10 @@ pub struct FasterKvBuilder<'a> {
     storage: Option<&'a str>,
     log_mutable_fraction: f64,
     pre_allocate_log: bool,
+    checkpoint_schedule: Option<CheckpointSchedule>,
+    compaction_policy: Option<CompactionPolicy>,
+    index_growth_policy: Option<IndexGrowthPolicy>,
+    recovery_tokens: Option<(&'a str, &'a str)>,
 }
 
 impl<'a> FasterKvBuilder<'a> {
@@ -17,6 +30,10 @@ impl<'a> FasterKvBuilder<'a> {
             storage: None,
             log_mutable_fraction: 0.9,
             pre_allocate_log: false,
+            checkpoint_schedule: None,
+            compaction_policy: None,
+            index_growth_policy: None,
+            recovery_tokens: None,
         }
     }
 
@@ -35,12 +52,140 @@ impl<'a> FasterKvBuilder<'a> {
         self
     }
 
+    /// Take checkpoints in the background, requires a disk-backed store
+    pub fn with_checkpoint_schedule(
+        &mut self,
+        schedule: CheckpointSchedule,
+    ) -> &mut FasterKvBuilder<'a> {
+        self.checkpoint_schedule = Some(schedule);
+        self
+    }
+
+    /// Compact the hybrid log in the background whenever it grows past the policy's size
+    pub fn with_compaction_policy(&mut self, policy: CompactionPolicy) -> &mut FasterKvBuilder<'a> {
+        self.compaction_policy = Some(policy);
+        self
+    }
+
+    /// Grow the hash index in the background whenever its load factor exceeds the policy's
+    pub fn with_index_growth_policy(
+        &mut self,
+        policy: IndexGrowthPolicy,
+    ) -> &mut FasterKvBuilder<'a> {
+        self.index_growth_policy = Some(policy);
+        self
+    }
+
+    /// Recover from the given checkpoints in `open_or_recover` instead of the latest ones
+    pub fn with_recovery_tokens(
+        &mut self,
+        index_token: &'a str,
+        hybrid_log_token: &'a str,
+    ) -> &mut FasterKvBuilder<'a> {
+        self.recovery_tokens = Some((index_token, hybrid_log_token));
+        self
+    }
+
     pub fn build(&self) -> Result<FasterKv, FasterError<'static>> {
+        self.build_with_codec()
+    }
+
+    /// Build a store whose keys and values are encoded with `C` instead of bincode
+    pub fn build_with_codec<C: Codec>(&self) -> Result<FasterKv<C>, FasterError<'static>> {
+        let mut store = self.open()?;
+        self.start_schedulers(&mut store);
+        Ok(store)
+    }
+
+    pub fn build_typed<K, V>(&self) -> Result<TypedFasterKv<K, V>, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        self.build_typed_with_codec()
+    }
+
+    pub fn build_typed_with_codec<K, V, C>(
+        &self,
+    ) -> Result<TypedFasterKv<K, V, C>, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+        C: Codec,
+    {
+        self.build_with_codec().map(TypedFasterKv::new)
+    }
+
+    /// Opens a disk-backed store, recovering it if the storage directory holds checkpoints.
+    ///
+    /// The latest recoverable pair of checkpoints is used unless tokens were pinned with
+    /// `with_recovery_tokens`. Returns `None` alongside the store if there was nothing to
+    /// recover.
+    pub fn open_or_recover(&self) -> Result<(FasterKv, Option<Recover>), FasterError<'static>> {
+        self.open_or_recover_with_codec()
+    }
+
+    pub fn open_or_recover_with_codec<C: Codec>(
+        &self,
+    ) -> Result<(FasterKv<C>, Option<Recover>), FasterError<'static>> {
+        let storage_dir = match self.storage {
+            Some(dir) => dir,
+            None => {
+                return Err(FasterError::BuilderError(
+                    "Recovery requires a disk-backed store",
+                ))
+            }
+        };
+        let tokens = match self.recovery_tokens {
+            Some((index_token, hybrid_log_token)) => {
+                Some((index_token.to_owned(), hybrid_log_token.to_owned()))
+            }
+            None => CheckpointCatalog::new(storage_dir)
+                .latest_recoverable()?
+                .map(|(index, hybrid_log)| (index.token, hybrid_log.token)),
+        };
+
+        let mut store: FasterKv<C> = self.open()?;
+        let recover = match tokens {
+            Some((index_token, hybrid_log_token)) => {
+                let recover = store.recover(index_token, hybrid_log_token)?;
+                if recover.status != status::OK {
+                    return Err(FasterError::RecoveryError);
+                }
+                Some(recover)
+            }
+            None => None,
+        };
+        // Only start the background work once the store has been recovered
+        self.start_schedulers(&mut store);
+        Ok((store, recover))
+    }
+
+    fn open<C: Codec>(&self) -> Result<FasterKv<C>, FasterError<'static>> {
         if !(self.log_mutable_fraction > 0.0 && self.log_mutable_fraction <= 1.0) {
             return Err(FasterError::BuilderError(
                 "Log mutable fraction must be between 0 and 1",
             ));
         }
+        if let Some(policy) = &self.compaction_policy {
+            if !policy.is_valid() {
+                return Err(FasterError::BuilderError(
+                    "Compaction fraction must be between 0 and 1",
+                ));
+            }
+        }
+        if let Some(policy) = &self.index_growth_policy {
+            if !policy.is_valid() {
+                return Err(FasterError::BuilderError(
+                    "Maximum load factor must be positive",
+                ));
+            }
+        }
+        if self.checkpoint_schedule.is_some() && self.storage.is_none() {
+            return Err(FasterError::BuilderError(
+                "Checkpoint schedule requires a disk-backed store",
+            ));
+        }
         unsafe {
             let mut storage_dir = None;
             let faster_t = match self.storage {
@@ -62,14 +207,38 @@ impl<'a> FasterKvBuilder<'a> {
             Ok(FasterKv {
                 faster_t,
                 storage_dir,
+                checkpoint_scheduler: None,
+                compaction_scheduler: None,
+                index_growth_scheduler: None,
+                codec: PhantomData,
             })
         }
     }
+
+    fn start_schedulers<C>(&self, store: &mut FasterKv<C>) {
+        if let (Some(schedule), Some(dir)) = (&self.checkpoint_schedule, &store.storage_dir) {
+            store.checkpoint_scheduler = Some(CheckpointScheduler::start(
+                store.faster_t,
+                dir,
+                schedule.clone(),
+            ));
+        }
+        if let Some(policy) = &self.compaction_policy {
+            store.compaction_scheduler =
+                Some(CompactionScheduler::start(store.faster_t, policy.clone()));
+        }
+        if let Some(policy) = &self.index_growth_policy {
+            store.index_growth_scheduler =
+                Some(IndexGrowthScheduler::start(store.faster_t, policy.clone()));
+        }
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::FasterKvBuilder;
+    use crate::{CheckpointSchedule, FasterError};
+    use std::time::Duration;
     use tempfile::TempDir;
     #[test]
     fn can_build_with_disk() {
@@ -84,4 +253,14 @@ pub mod tests {
         let storage = &kv.storage_dir;
         assert_eq!(storage.as_ref().unwrap(), dir_str);
     }
+
+    #[test]
+    fn checkpoint_schedule_requires_disk() {
+        let mut builder = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024);
+        builder.with_checkpoint_schedule(CheckpointSchedule::every(Duration::from_secs(1)));
+        match builder.build() {
+            Err(FasterError::BuilderError(_)) => assert!(true),
+            _ => assert!(false, "Should give BuilderError"),
+        }
+    }
 }
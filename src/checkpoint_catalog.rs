@@ -0,0 +1,263 @@
+use crate::FasterError;
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// Directory layout used by FASTER's FileSystemDisk
+const INDEX_CHECKPOINT_DIR: &str = "index-checkpoints";
+const HYBRID_LOG_CHECKPOINT_DIR: &str = "cpr-checkpoints";
+// Written once the checkpoint's metadata is complete
+const METADATA_FILE: &str = "info.dat";
+// Offset of the version in IndexMetadata and LogMetadata respectively
+const INDEX_VERSION_OFFSET: u64 = 0;
+const HYBRID_LOG_VERSION_OFFSET: u64 = 4;
+// Layout of the sessions in LogMetadata: the number of sessions, then one serial number
+// and one GUID per thread slot
+const NUM_SESSIONS_OFFSET: usize = 8;
+const SERIAL_NUMBERS_OFFSET: usize = 32;
+const GUIDS_OFFSET: usize = 800;
+const MAX_SESSIONS: usize = 96;
+const GUID_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointKind {
+    Index,
+    HybridLog,
+}
+
+impl CheckpointKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            CheckpointKind::Index => INDEX_CHECKPOINT_DIR,
+            CheckpointKind::HybridLog => HYBRID_LOG_CHECKPOINT_DIR,
+        }
+    }
+
+    fn version_offset(self) -> u64 {
+        match self {
+            CheckpointKind::Index => INDEX_VERSION_OFFSET,
+            CheckpointKind::HybridLog => HYBRID_LOG_VERSION_OFFSET,
+        }
+    }
+}
+
+/// A completed checkpoint found in the storage directory
+#[derive(Clone, Debug)]
+pub struct CheckpointInfo {
+    pub token: String,
+    pub kind: CheckpointKind,
+    pub version: u32,
+    pub created: SystemTime,
+    /// Total size in bytes of the checkpoint's files
+    pub size: u64,
+    path: PathBuf,
+}
+
+/// Which checkpoints `CheckpointCatalog::apply_retention` keeps
+#[derive(Clone, Copy, Debug)]
+pub enum RetentionPolicy {
+    /// Keep the given number of most recent checkpoints of each kind
+    KeepLatest(usize),
+    /// Keep checkpoints created within the given duration
+    MaxAge(Duration),
+}
+
+/// Enumerates and manages the checkpoints in a store's storage directory
+pub struct CheckpointCatalog {
+    storage_dir: PathBuf,
+}
+
+impl CheckpointCatalog {
+    pub fn new<P: AsRef<Path>>(storage_dir: P) -> CheckpointCatalog {
+        CheckpointCatalog {
+            storage_dir: storage_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Lists completed checkpoints of both kinds, oldest first.
+    /// Checkpoints still being written are not included.
+    pub fn list(&self) -> Result<Vec<CheckpointInfo>, FasterError<'static>> {
+        let mut checkpoints = self.list_kind(CheckpointKind::Index)?;
+        checkpoints.append(&mut self.list_kind(CheckpointKind::HybridLog)?);
+        checkpoints.sort_by_key(|checkpoint| (checkpoint.created, checkpoint.version));
+        Ok(checkpoints)
+    }
+
+    /// Lists completed checkpoints of one kind, oldest first
+    pub fn list_kind(
+        &self,
+        kind: CheckpointKind,
+    ) -> Result<Vec<CheckpointInfo>, FasterError<'static>> {
+        let dir = self.storage_dir.join(kind.dir_name());
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut checkpoints = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if let Some(checkpoint) = read_checkpoint(kind, path)? {
+                checkpoints.push(checkpoint);
+            }
+        }
+        checkpoints.sort_by_key(|checkpoint| (checkpoint.created, checkpoint.version));
+        Ok(checkpoints)
+    }
+
+    /// The most recent (index, hybrid log) pair that can be passed to `FasterKv::recover`.
+    ///
+    /// A full checkpoint provides both halves under the same token, otherwise the
+    /// newest hybrid log checkpoint is paired with the newest index checkpoint taken
+    /// no later than it.
+    pub fn latest_recoverable(
+        &self,
+    ) -> Result<Option<(CheckpointInfo, CheckpointInfo)>, FasterError<'static>> {
+        let indexes = self.list_kind(CheckpointKind::Index)?;
+        let hybrid_logs = self.list_kind(CheckpointKind::HybridLog)?;
+        for hybrid_log in hybrid_logs.into_iter().rev() {
+            let index = indexes
+                .iter()
+                .find(|index| index.token == hybrid_log.token)
+                .or_else(|| {
+                    indexes.iter().rev().find(|index| {
+                        index.created <= hybrid_log.created && index.version <= hybrid_log.version
+                    })
+                });
+            if let Some(index) = index {
+                return Ok(Some((index.clone(), hybrid_log)));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, checkpoint: &CheckpointInfo) -> Result<(), FasterError<'static>> {
+        fs::remove_dir_all(&checkpoint.path)?;
+        Ok(())
+    }
+
+    /// Deletes the checkpoints not kept by `policy` and returns them.
+    /// The latest recoverable pair is always kept.
+    pub fn apply_retention(
+        &self,
+        policy: RetentionPolicy,
+    ) -> Result<Vec<CheckpointInfo>, FasterError<'static>> {
+        let latest = self.latest_recoverable()?;
+        let is_latest = |checkpoint: &CheckpointInfo| match &latest {
+            Some((index, hybrid_log)) => {
+                checkpoint.path == index.path || checkpoint.path == hybrid_log.path
+            }
+            None => false,
+        };
+
+        let mut expired = Vec::new();
+        for kind in &[CheckpointKind::Index, CheckpointKind::HybridLog] {
+            let checkpoints = self.list_kind(*kind)?;
+            let count = checkpoints.len();
+            for (position, checkpoint) in checkpoints.into_iter().enumerate() {
+                let keep = match policy {
+                    RetentionPolicy::KeepLatest(n) => position + n >= count,
+                    RetentionPolicy::MaxAge(max_age) => match checkpoint.created.elapsed() {
+                        Ok(age) => age <= max_age,
+                        // Created in the future according to our clock
+                        Err(_) => true,
+                    },
+                };
+                if !keep && !is_latest(&checkpoint) {
+                    expired.push(checkpoint);
+                }
+            }
+        }
+
+        for checkpoint in &expired {
+            self.delete(checkpoint)?;
+        }
+        Ok(expired)
+    }
+}
+
+/// Reads the GUID of each session persisted by a hybrid log checkpoint, together with
+/// the serial number the session was persisted up to
+pub(crate) fn read_persisted_sessions(
+    storage_dir: &str,
+    hybrid_log_token: &str,
+) -> Result<Vec<(String, u64)>, FasterError<'static>> {
+    let metadata_path = Path::new(storage_dir)
+        .join(HYBRID_LOG_CHECKPOINT_DIR)
+        .join(hybrid_log_token)
+        .join(METADATA_FILE);
+    let metadata = fs::read(metadata_path)?;
+    if metadata.len() < GUIDS_OFFSET + MAX_SESSIONS * GUID_LENGTH {
+        return Err(FasterError::RecoveryError);
+    }
+
+    let mut num_sessions = [0; 4];
+    num_sessions.copy_from_slice(&metadata[NUM_SESSIONS_OFFSET..NUM_SESSIONS_OFFSET + 4]);
+    let num_sessions = u32::from_le_bytes(num_sessions) as usize;
+    if num_sessions > MAX_SESSIONS {
+        return Err(FasterError::RecoveryError);
+    }
+    let sessions = (0..num_sessions)
+        .map(|idx| {
+            let mut serial_number = [0; 8];
+            let serial_offset = SERIAL_NUMBERS_OFFSET + idx * 8;
+            serial_number.copy_from_slice(&metadata[serial_offset..serial_offset + 8]);
+            let guid_offset = GUIDS_OFFSET + idx * GUID_LENGTH;
+            let guid = format_guid(&metadata[guid_offset..guid_offset + GUID_LENGTH]);
+            (guid, u64::from_le_bytes(serial_number))
+        })
+        .collect();
+    Ok(sessions)
+}
+
+// Formats the bytes of a GUID the way FASTER's Guid::ToString does
+fn format_guid(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex[0..4].concat(),
+        hex[4..6].concat(),
+        hex[6..8].concat(),
+        hex[8..10].concat(),
+        hex[10..16].concat()
+    )
+}
+
+fn read_checkpoint(
+    kind: CheckpointKind,
+    path: PathBuf,
+) -> Result<Option<CheckpointInfo>, FasterError<'static>> {
+    let token = match path.file_name().and_then(|name| name.to_str()) {
+        Some(token) if path.is_dir() => token.to_owned(),
+        _ => return Ok(None),
+    };
+    let metadata_path = path.join(METADATA_FILE);
+    let mut metadata_file = match File::open(&metadata_path) {
+        Ok(file) => file,
+        Err(_) => return Ok(None),
+    };
+    let mut version = [0; 4];
+    metadata_file.seek(SeekFrom::Start(kind.version_offset()))?;
+    if metadata_file.read_exact(&mut version).is_err() {
+        return Ok(None);
+    }
+    let metadata = metadata_file.metadata()?;
+    let created = metadata.created().or_else(|_| metadata.modified())?;
+
+    let mut size = 0;
+    for entry in fs::read_dir(&path)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+
+    Ok(Some(CheckpointInfo {
+        token,
+        kind,
+        version: u32::from_le_bytes(version),
+        created,
+        size,
+        path,
+    }))
+}
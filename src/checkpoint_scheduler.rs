@@ -0,0 +1,224 @@
+use crate::instrumentation;
+use crate::util::{take_checkpoint, BackgroundThread, CheckpointFn, FasterPtr};
+use crate::{CheckpointCatalog, CheckpointKind, RetentionPolicy};
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+// How often the scheduler checks its trigger and for durable checkpoints
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Number of checkpoints remembered by the scheduler
+const HISTORY_LENGTH: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Checkpoint both the hash index and the hybrid log
+    Full,
+    /// Checkpoint only the hybrid log
+    HybridLog,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointTrigger {
+    /// Checkpoint at a fixed interval
+    Interval(Duration),
+    /// Checkpoint once the log has grown by the given number of bytes
+    LogGrowth(u64),
+}
+
+/// A checkpoint taken by the scheduler
+#[derive(Clone, Debug)]
+pub struct CheckpointRecord {
+    pub mode: CheckpointMode,
+    /// `None` if FASTER refused to start the checkpoint, e.g. because another was in progress
+    pub token: Option<String>,
+    pub started: SystemTime,
+    /// When the checkpoint was observed to be complete on disk
+    pub durable: Option<SystemTime>,
+}
+
+/// Configures the background checkpoints taken by a `FasterKv`
+#[derive(Clone)]
+pub struct CheckpointSchedule {
+    mode: CheckpointMode,
+    trigger: CheckpointTrigger,
+    retention: Option<RetentionPolicy>,
+    on_durable: Option<Arc<dyn Fn(&CheckpointRecord) + Send + Sync>>,
+}
+
+impl CheckpointSchedule {
+    pub fn new(trigger: CheckpointTrigger) -> CheckpointSchedule {
+        CheckpointSchedule {
+            mode: CheckpointMode::Full,
+            trigger,
+            retention: None,
+            on_durable: None,
+        }
+    }
+
+    pub fn every(interval: Duration) -> CheckpointSchedule {
+        CheckpointSchedule::new(CheckpointTrigger::Interval(interval))
+    }
+
+    pub fn on_log_growth(bytes: u64) -> CheckpointSchedule {
+        CheckpointSchedule::new(CheckpointTrigger::LogGrowth(bytes))
+    }
+
+    pub fn with_mode(mut self, mode: CheckpointMode) -> CheckpointSchedule {
+        self.mode = mode;
+        self
+    }
+
+    /// Delete old checkpoints after each durable checkpoint
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> CheckpointSchedule {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Called from the scheduler thread once a checkpoint is durable
+    pub fn on_durable<F>(mut self, hook: F) -> CheckpointSchedule
+    where
+        F: Fn(&CheckpointRecord) + Send + Sync + 'static,
+    {
+        self.on_durable = Some(Arc::new(hook));
+        self
+    }
+}
+
+/// Thread taking checkpoints according to a `CheckpointSchedule`
+pub(crate) struct CheckpointScheduler {
+    history: Arc<Mutex<VecDeque<CheckpointRecord>>>,
+    thread: BackgroundThread,
+}
+
+impl CheckpointScheduler {
+    pub(crate) fn start(
+        faster_t: *mut ffi::faster_t,
+        storage_dir: &str,
+        schedule: CheckpointSchedule,
+    ) -> CheckpointScheduler {
+        let history = Arc::new(Mutex::new(VecDeque::with_capacity(HISTORY_LENGTH)));
+        let mut state = SchedulerState {
+            faster_t: FasterPtr(faster_t),
+            catalog: CheckpointCatalog::new(storage_dir),
+            last_checkpoint: Instant::now(),
+            last_size: unsafe { ffi::faster_size(faster_t) },
+            awaiting_durable: Vec::new(),
+            history: Arc::clone(&history),
+            schedule,
+        };
+        let thread =
+            BackgroundThread::start("faster-checkpoint", POLL_INTERVAL, move || state.tick());
+        CheckpointScheduler { history, thread }
+    }
+
+    pub(crate) fn history(&self) -> Vec<CheckpointRecord> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Stops the thread, which must happen before the store is destroyed
+    pub(crate) fn stop(&mut self) {
+        self.thread.stop();
+    }
+}
+
+struct SchedulerState {
+    faster_t: FasterPtr,
+    catalog: CheckpointCatalog,
+    schedule: CheckpointSchedule,
+    last_checkpoint: Instant,
+    last_size: u64,
+    awaiting_durable: Vec<CheckpointRecord>,
+    history: Arc<Mutex<VecDeque<CheckpointRecord>>>,
+}
+
+impl SchedulerState {
+    fn tick(&mut self) {
+        let (checkpoint_fn, kind): (CheckpointFn, _) = match self.schedule.mode {
+            CheckpointMode::Full => (ffi::faster_checkpoint, instrumentation::FULL),
+            CheckpointMode::HybridLog => (
+                ffi::faster_checkpoint_hybrid_log,
+                instrumentation::HYBRID_LOG,
+            ),
+        };
+        let size = unsafe { ffi::faster_size(self.faster_t.0) };
+        let triggered = match self.schedule.trigger {
+            CheckpointTrigger::Interval(interval) => self.last_checkpoint.elapsed() >= interval,
+            CheckpointTrigger::LogGrowth(bytes) => size.saturating_sub(self.last_size) >= bytes,
+        };
+        if triggered {
+            let started = SystemTime::now();
+            let token = match unsafe { take_checkpoint(self.faster_t.0, checkpoint_fn, kind) } {
+                Ok(ref checkpoint) if checkpoint.checked => Some(checkpoint.token.clone()),
+                _ => None,
+            };
+            let record = CheckpointRecord {
+                mode: self.schedule.mode,
+                token,
+                started,
+                durable: None,
+            };
+            if record.token.is_some() {
+                self.awaiting_durable.push(record.clone());
+            }
+            push_history(&self.history, record);
+            self.last_checkpoint = Instant::now();
+            self.last_size = size;
+        }
+
+        let mut still_waiting = Vec::with_capacity(self.awaiting_durable.len());
+        for mut record in self.awaiting_durable.drain(..) {
+            if !is_durable(&self.catalog, &record) {
+                still_waiting.push(record);
+                continue;
+            }
+            let durable = SystemTime::now();
+            record.durable = Some(durable);
+            if let Ok(duration) = durable.duration_since(record.started) {
+                instrumentation::checkpoint_durable(kind, duration);
+            }
+            update_history(&self.history, &record);
+            if let Some(retention) = self.schedule.retention {
+                // A failed cleanup is retried after the next checkpoint
+                let _ = self.catalog.apply_retention(retention);
+            }
+            if let Some(hook) = &self.schedule.on_durable {
+                hook(&record);
+            }
+        }
+        self.awaiting_durable = still_waiting;
+    }
+}
+
+// A checkpoint is durable once its metadata has been written for every part it covers
+fn is_durable(catalog: &CheckpointCatalog, record: &CheckpointRecord) -> bool {
+    let kinds: &[CheckpointKind] = match record.mode {
+        CheckpointMode::Full => &[CheckpointKind::Index, CheckpointKind::HybridLog],
+        CheckpointMode::HybridLog => &[CheckpointKind::HybridLog],
+    };
+    kinds.iter().all(|kind| match catalog.list_kind(*kind) {
+        Ok(checkpoints) => checkpoints
+            .iter()
+            .any(|checkpoint| Some(&checkpoint.token) == record.token.as_ref()),
+        Err(_) => false,
+    })
+}
+
+fn push_history(history: &Mutex<VecDeque<CheckpointRecord>>, record: CheckpointRecord) {
+    let mut history = history.lock().unwrap();
+    if history.len() == HISTORY_LENGTH {
+        history.pop_front();
+    }
+    history.push_back(record);
+}
+
+fn update_history(history: &Mutex<VecDeque<CheckpointRecord>>, record: &CheckpointRecord) {
+    let mut history = history.lock().unwrap();
+    if let Some(entry) = history
+        .iter_mut()
+        .find(|entry| entry.token == record.token && entry.started == record.started)
+    {
+        entry.durable = record.durable;
+    }
+}
@@ -0,0 +1,157 @@
+use crate::FasterError;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encodes the keys and values of a `FasterKv`.
+///
+/// Every thread reading or writing a store must use the same codec, so it is
+/// part of the store's type. Implement this trait to use another format or
+/// different options for an existing one.
+pub trait Codec {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>>;
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>>;
+
+    /// Appends the encoding of `value` to `buffer`, used by batches to reuse one allocation
+    fn encode_into<T: Serialize + ?Sized>(
+        value: &T,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FasterError<'static>> {
+        buffer.extend_from_slice(&Self::encode(value)?);
+        Ok(())
+    }
+}
+
+/// bincode with its default options, used unless another codec is chosen
+pub struct Bincode;
+
+impl Codec for Bincode {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>> {
+        bincode::serialize(value).map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>> {
+        bincode::deserialize(bytes)
+            .map_err(|err| FasterError::DeserializationError(err.to_string()))
+    }
+
+    fn encode_into<T: Serialize + ?Sized>(
+        value: &T,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FasterError<'static>> {
+        bincode::serialize_into(buffer, value)
+            .map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+}
+
+#[cfg(feature = "serde_json")]
+pub struct Json;
+
+#[cfg(feature = "serde_json")]
+impl Codec for Json {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>> {
+        serde_json::to_vec(value).map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>> {
+        serde_json::from_slice(bytes)
+            .map_err(|err| FasterError::DeserializationError(err.to_string()))
+    }
+
+    fn encode_into<T: Serialize + ?Sized>(
+        value: &T,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FasterError<'static>> {
+        serde_json::to_writer(buffer, value)
+            .map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+}
+
+/// MessagePack, encoding structs as maps so other implementations can read them by field name
+#[cfg(feature = "rmp-serde")]
+pub struct MessagePack;
+
+#[cfg(feature = "rmp-serde")]
+impl Codec for MessagePack {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>> {
+        rmp_serde::to_vec_named(value)
+            .map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|err| FasterError::DeserializationError(err.to_string()))
+    }
+
+    fn encode_into<T: Serialize + ?Sized>(
+        value: &T,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FasterError<'static>> {
+        rmp_serde::encode::write_named(buffer, value)
+            .map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+}
+
+#[cfg(feature = "postcard")]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl Codec for Postcard {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>> {
+        postcard::to_allocvec(value).map_err(|err| FasterError::SerializationError(err.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>> {
+        postcard::from_bytes(bytes)
+            .map_err(|err| FasterError::DeserializationError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{Bincode, Codec};
+    use crate::FasterError;
+
+    fn round_trip<C: Codec>() {
+        let value = (42u64, String::from("value"), vec![1u8, 2, 3]);
+        let encoded = C::encode(&value).unwrap();
+        let decoded: (u64, String, Vec<u8>) = C::decode(&encoded).unwrap();
+        assert_eq!(decoded, value);
+
+        let mut buffer = vec![0xff];
+        C::encode_into(&value, &mut buffer).unwrap();
+        assert_eq!(&buffer[1..], encoded.as_slice());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        round_trip::<Bincode>();
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_round_trip() {
+        round_trip::<super::Json>();
+    }
+
+    #[cfg(feature = "rmp-serde")]
+    #[test]
+    fn message_pack_round_trip() {
+        round_trip::<super::MessagePack>();
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn postcard_round_trip() {
+        round_trip::<super::Postcard>();
+    }
+
+    #[test]
+    fn decode_error() {
+        match Bincode::decode::<String>(&[1]) {
+            Err(FasterError::DeserializationError(_)) => assert!(true),
+            _ => assert!(false, "Should give DeserializationError"),
+        }
+    }
+}
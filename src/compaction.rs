@@ -0,0 +1,121 @@
+use crate::util::{BackgroundThread, FasterPtr};
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// A compaction run by a `CompactionPolicy`
+#[derive(Clone, Debug)]
+pub struct CompactionRecord {
+    /// Begin address of the log before compacting
+    pub from_address: u64,
+    /// New begin address of the log, if FASTER compacted it
+    pub until_address: u64,
+    pub succeeded: bool,
+    pub started: SystemTime,
+    pub finished: SystemTime,
+}
+
+/// Configures the background compaction of a `FasterKv`'s hybrid log.
+///
+/// Whenever the log holds more than `max_log_bytes` between its begin and tail
+/// addresses, the oldest `fraction` of it is compacted: live records are copied
+/// to the tail and the begin address is moved past them.
+#[derive(Clone)]
+pub struct CompactionPolicy {
+    max_log_bytes: u64,
+    fraction: f64,
+    check_interval: Duration,
+    on_compacted: Option<Arc<dyn Fn(&CompactionRecord) + Send + Sync>>,
+}
+
+impl CompactionPolicy {
+    pub fn on_log_size(max_log_bytes: u64) -> CompactionPolicy {
+        CompactionPolicy {
+            max_log_bytes,
+            fraction: 0.25,
+            check_interval: Duration::from_secs(1),
+            on_compacted: None,
+        }
+    }
+
+    /// Portion of the log compacted at once, 0.25 by default
+    pub fn with_fraction(mut self, fraction: f64) -> CompactionPolicy {
+        self.fraction = fraction;
+        self
+    }
+
+    /// How often the size of the log is checked, once a second by default
+    pub fn with_check_interval(mut self, interval: Duration) -> CompactionPolicy {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Called from the compaction thread after each compaction
+    pub fn on_compacted<F>(mut self, hook: F) -> CompactionPolicy
+    where
+        F: Fn(&CompactionRecord) + Send + Sync + 'static,
+    {
+        self.on_compacted = Some(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.fraction > 0.0 && self.fraction <= 1.0
+    }
+}
+
+/// Thread compacting the log according to a `CompactionPolicy`
+pub(crate) struct CompactionScheduler {
+    thread: BackgroundThread,
+}
+
+impl CompactionScheduler {
+    pub(crate) fn start(
+        faster_t: *mut ffi::faster_t,
+        policy: CompactionPolicy,
+    ) -> CompactionScheduler {
+        let faster_t = FasterPtr(faster_t);
+        let thread =
+            BackgroundThread::start("faster-compaction", policy.check_interval, move || {
+                if let Some(record) = maybe_compact(&faster_t, &policy) {
+                    if let Some(hook) = &policy.on_compacted {
+                        hook(&record);
+                    }
+                }
+            });
+        CompactionScheduler { thread }
+    }
+
+    /// Stops the thread, which must happen before the store is destroyed
+    pub(crate) fn stop(&mut self) {
+        self.thread.stop();
+    }
+}
+
+fn maybe_compact(faster_t: &FasterPtr, policy: &CompactionPolicy) -> Option<CompactionRecord> {
+    let (begin, tail, safe_read_only) = unsafe {
+        (
+            ffi::faster_log_begin_address(faster_t.0),
+            ffi::faster_log_tail_address(faster_t.0),
+            ffi::faster_log_safe_read_only_address(faster_t.0),
+        )
+    };
+    let log_bytes = tail.saturating_sub(begin);
+    if log_bytes <= policy.max_log_bytes {
+        return None;
+    }
+    // Records in the mutable region may still be updated in place, so they are never compacted
+    let until = (begin + (log_bytes as f64 * policy.fraction) as u64).min(safe_read_only);
+    if until <= begin {
+        return None;
+    }
+    let started = SystemTime::now();
+    let succeeded = unsafe { ffi::faster_compact(faster_t.0, until) };
+    Some(CompactionRecord {
+        from_address: begin,
+        until_address: until,
+        succeeded,
+        started,
+        finished: SystemTime::now(),
+    })
+}
@@ -1,3 +1,4 @@
+use crate::status::Status;
 use std::error::Error;
 use std::{fmt, io};
 
@@ -7,7 +8,13 @@ pub enum FasterError<'a> {
     InvalidType,
     RecoveryError,
     CheckpointError,
+    CompactionError,
     BuilderError(&'a str),
+    OperationError(Status),
+    InvalidStatus(u8),
+    SerialNumberError(u64, u64),
+    SerializationError(String),
+    DeserializationError(String),
 }
 
 impl<'a> fmt::Display for FasterError<'a> {
@@ -17,7 +24,21 @@ impl<'a> fmt::Display for FasterError<'a> {
             FasterError::InvalidType => write!(f, "Cannot call method with in-memory FasterKv"),
             FasterError::RecoveryError => write!(f, "Failed to recover"),
             FasterError::CheckpointError => write!(f, "Checkpoint failed"),
+            FasterError::CompactionError => write!(f, "Compaction failed"),
             FasterError::BuilderError(err) => write!(f, "Builder error: {}", err),
+            FasterError::OperationError(status) => {
+                write!(f, "Operation failed with status {:?}", status)
+            }
+            FasterError::InvalidStatus(value) => write!(f, "Unknown status value {}", value),
+            FasterError::SerialNumberError(serial_number, last) => write!(
+                f,
+                "Serial number {} does not follow the session's serial number {}",
+                serial_number, last
+            ),
+            FasterError::SerializationError(err) => write!(f, "Failed to serialize value: {}", err),
+            FasterError::DeserializationError(err) => {
+                write!(f, "Failed to deserialize value: {}", err)
+            }
         }
     }
 }
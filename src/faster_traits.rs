@@ -1,38 +1,251 @@
-extern crate bincode;
 extern crate libc;
 extern crate libfaster_sys as ffi;
 
-use crate::status;
+use crate::codec::Codec;
+use crate::status::Status;
+use crate::{FasterError, ReadResult};
 
-use bincode::deserialize;
+use futures::channel::oneshot;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::convert::TryFrom;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::mpsc::Sender;
 
 pub trait FasterKey: DeserializeOwned + Serialize {}
 
 pub trait FasterValue: DeserializeOwned + Serialize {}
 
+/// The sending half of a channel that a read delivers its value to
+pub trait ReadSender<T> {
+    fn send_value(self, value: T);
+}
+
+impl<T> ReadSender<T> for Sender<T> {
+    fn send_value(self, value: T) {
+        // TODO: log error
+        let _ = self.send(value);
+    }
+}
+
+impl<T> ReadSender<T> for oneshot::Sender<T> {
+    fn send_value(self, value: T) {
+        let _ = self.send(value);
+    }
+}
+
+/// Tags each read of a batch with its position, so every read can share one channel
+pub struct IndexedSender<T> {
+    pub index: usize,
+    pub sender: Sender<(usize, T)>,
+}
+
+impl<T> ReadSender<T> for IndexedSender<T> {
+    fn send_value(self, value: T) {
+        let _ = self.sender.send((self.index, value));
+    }
+}
+
 #[inline(always)]
-pub unsafe extern "C" fn read_callback<T>(
+pub unsafe extern "C" fn read_callback<T, S, C>(
     sender: *mut libc::c_void,
     value: *const u8,
     length: u64,
     status: u32,
 ) where
     T: DeserializeOwned,
+    S: ReadSender<ReadResult<T>>,
+    C: Codec,
 {
-    let boxed_sender = Box::from_raw(sender as *mut Sender<T>);
+    let boxed_sender = Box::from_raw(sender as *mut S);
     let sender = *boxed_sender;
-    if status == status::OK.into() {
-        let val = deserialize(std::slice::from_raw_parts(value, length as usize)).unwrap();
-        // TODO: log error
-        let _ = sender.send(val);
+    // Unwinding into FASTER is undefined behaviour so panics are reported as errors
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        read_result::<T, C>(value, length, status)
+    }))
+    .unwrap_or_else(|_| {
+        Err(FasterError::DeserializationError(String::from(
+            "panicked while deserializing value",
+        )))
+    });
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| sender.send_value(result)));
+}
+
+unsafe fn read_result<T, C>(value: *const u8, length: u64, status: u32) -> ReadResult<T>
+where
+    T: DeserializeOwned,
+    C: Codec,
+{
+    match Status::try_from(status as u8)? {
+        Status::Ok => C::decode(std::slice::from_raw_parts(value, length as usize)).map(Some),
+        Status::NotFound => Ok(None),
+        status => Err(FasterError::OperationError(status)),
+    }
+}
+
+/// Receives the value of `FasterKv::read_bytes`, borrowed from FASTER's log
+pub type BytesReadResult<'v> = Result<Option<&'v [u8]>, FasterError<'static>>;
+
+#[inline(always)]
+pub unsafe extern "C" fn read_bytes_callback<F>(
+    callback: *mut libc::c_void,
+    value: *const u8,
+    length: u64,
+    status: u32,
+) where
+    F: FnOnce(BytesReadResult),
+{
+    let callback = *Box::from_raw(callback as *mut F);
+    let result = match Status::try_from(status as u8) {
+        Ok(Status::Ok) => Ok(Some(std::slice::from_raw_parts(value, length as usize))),
+        Ok(Status::NotFound) => Ok(None),
+        Ok(status) => Err(FasterError::OperationError(status)),
+        Err(err) => Err(err),
+    };
+    // Unwinding into FASTER is undefined behaviour
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(result)));
+}
+
+#[inline(always)]
+pub unsafe extern "C" fn rmw_bytes_callback<F>(
+    merge: *mut libc::c_void,
+    current: *const u8,
+    length_current: u64,
+    modification: *const u8,
+    length_modification: u64,
+    dst: *mut u8,
+) -> u64
+where
+    F: Fn(&[u8], &[u8]) -> Vec<u8>,
+{
+    let merge = &*(merge as *const F);
+    let current = std::slice::from_raw_parts(current, length_current as usize);
+    let modification = std::slice::from_raw_parts(modification, length_modification as usize);
+    // As in rmw_callback, the current value is kept if the merge panics
+    let merged = panic::catch_unwind(AssertUnwindSafe(|| merge(current, modification)));
+    let encoded = match merged {
+        Ok(ref merged) => merged.as_slice(),
+        Err(_) => current,
+    };
+    let size = encoded.len();
+    if dst != std::ptr::null_mut() {
+        encoded.as_ptr().copy_to(dst, size);
+    }
+    size as u64
+}
+
+/// Returned by an RMW callback to leave the record as it is, or not to create
+/// one if the key does not exist
+pub const RMW_UNCHANGED: u64 = u64::MAX;
+
+#[inline(always)]
+pub unsafe extern "C" fn rmw_with_callback<V, I, F, C>(
+    update: *mut libc::c_void,
+    current: *const u8,
+    length_current: u64,
+    input: *const u8,
+    length_input: u64,
+    dst: *mut u8,
+) -> u64
+where
+    V: DeserializeOwned + Serialize,
+    I: DeserializeOwned,
+    F: Fn(Option<&V>, I) -> Option<V>,
+    C: Codec,
+{
+    let update = &*(update as *const F);
+    // A null current value means the key does not exist yet
+    let current = match current.is_null() {
+        true => None,
+        false => Some(std::slice::from_raw_parts(current, length_current as usize)),
+    };
+    let input = std::slice::from_raw_parts(input, length_input as usize);
+    let updated = panic::catch_unwind(AssertUnwindSafe(|| {
+        let value: Option<V> = match current {
+            Some(current) => Some(C::decode(current).ok()?),
+            None => None,
+        };
+        let input = C::decode(input).ok()?;
+        C::encode(&update(value.as_ref(), input)?).ok()
+    }));
+    // The record is left alone if there is no update or it fails
+    let encoded = match updated {
+        Ok(Some(encoded)) => encoded,
+        _ => return RMW_UNCHANGED,
+    };
+    let size = encoded.len();
+    if dst != std::ptr::null_mut() {
+        encoded.as_ptr().copy_to(dst, size);
+    }
+    size as u64
+}
+
+/// Frees the context of an operation once FASTER has completed it
+pub unsafe extern "C" fn release_context<F>(context: *mut libc::c_void) {
+    drop(Box::from_raw(context as *mut F));
+}
+
+/// FASTER's InitialUpdater: creates the value of a missing key from the modification
+#[inline(always)]
+pub unsafe extern "C" fn rmw_initial_callback<T, C>(
+    modification: *const u8,
+    length_modification: u64,
+    dst: *mut u8,
+) -> u64
+where
+    T: FasterRmw,
+    C: Codec,
+{
+    let modification = std::slice::from_raw_parts(modification, length_modification as usize);
+    let initial =
+        panic::catch_unwind(|| C::encode(&T::initial(C::decode(modification).ok()?)).ok());
+    // Like a failed update, a failed initialisation stores the modification unchanged
+    let encoded = match initial {
+        Ok(Some(ref encoded)) => encoded.as_slice(),
+        _ => modification,
+    };
+    let size = encoded.len();
+    if dst != std::ptr::null_mut() {
+        encoded.as_ptr().copy_to(dst, size);
+    }
+    size as u64
+}
+
+/// FASTER's InPlaceUpdater: returns false if the value cannot be updated without
+/// changing the size of its encoding, in which case FASTER falls back to `rmw_callback`.
+/// The C interface holds the record's lock while this runs.
+#[inline(always)]
+pub unsafe extern "C" fn rmw_in_place_callback<T, C>(
+    current: *mut u8,
+    length_current: u64,
+    modification: *const u8,
+    length_modification: u64,
+) -> bool
+where
+    T: FasterRmw,
+    C: Codec,
+{
+    let current = std::slice::from_raw_parts_mut(current, length_current as usize);
+    let modification = std::slice::from_raw_parts(modification, length_modification as usize);
+    let updated = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut value: T = C::decode(current).ok()?;
+        if !value.rmw_in_place(C::decode(modification).ok()?) {
+            return None;
+        }
+        C::encode(&value).ok()
+    }));
+    match updated {
+        Ok(Some(ref encoded)) if encoded.len() == current.len() => {
+            current.copy_from_slice(encoded);
+            true
+        }
+        _ => false,
     }
 }
 
+/// FASTER's CopyUpdater: writes the updated value to a new record
 #[inline(always)]
-pub unsafe extern "C" fn rmw_callback<T>(
+pub unsafe extern "C" fn rmw_callback<T, C>(
     current: *const u8,
     length_current: u64,
     modification: *mut u8,
@@ -41,15 +254,21 @@ pub unsafe extern "C" fn rmw_callback<T>(
 ) -> u64
 where
     T: Serialize + DeserializeOwned + FasterRmw,
+    C: Codec,
 {
-    let val: T = deserialize(std::slice::from_raw_parts(current, length_current as usize)).unwrap();
-    let modif = deserialize(std::slice::from_raw_parts_mut(
-        modification,
-        length_modification as usize,
-    ))
-    .unwrap();
-    let modified = val.rmw(modif);
-    let encoded = bincode::serialize(&modified).unwrap();
+    let current = std::slice::from_raw_parts(current, length_current as usize);
+    let modification = std::slice::from_raw_parts(modification, length_modification as usize);
+    let modified = panic::catch_unwind(|| {
+        let val: T = C::decode(current).ok()?;
+        let modif = C::decode(modification).ok()?;
+        C::encode(&val.rmw(modif)).ok()
+    });
+    // There is no way to report an error to FASTER, so if the modification
+    // cannot be applied the current value is kept rather than unwinding into C
+    let encoded = match modified {
+        Ok(Some(ref encoded)) => encoded.as_slice(),
+        _ => current,
+    };
     let size = encoded.len();
     if dst != std::ptr::null_mut() {
         encoded.as_ptr().copy_to(dst, size);
@@ -57,12 +276,76 @@ where
     size as u64
 }
 
+/// Plain-old-data types that are passed to FASTER as fixed-size records,
+/// without serialization, by the `_pod` operations.
+///
+/// # Safety
+/// The type must have no padding bytes, no pointers or references, and every
+/// bit pattern of its size must be a valid value.
+pub unsafe trait Pod: Copy + 'static {}
+
+#[inline(always)]
+pub unsafe extern "C" fn read_pod_callback<V, F>(
+    callback: *mut libc::c_void,
+    value: *const u8,
+    length: u64,
+    status: u32,
+) where
+    V: Pod,
+    F: FnOnce(ReadResult<V>),
+{
+    let callback = *Box::from_raw(callback as *mut F);
+    let result = match Status::try_from(status as u8) {
+        Ok(Status::Ok) if length as usize == std::mem::size_of::<V>() => {
+            Ok(Some(std::ptr::read_unaligned(value as *const V)))
+        }
+        // The record was not written by upsert_pod with this value type
+        Ok(Status::Ok) => Err(FasterError::DeserializationError(format!(
+            "expected {} bytes, found {}",
+            std::mem::size_of::<V>(),
+            length
+        ))),
+        Ok(Status::NotFound) => Ok(None),
+        Ok(status) => Err(FasterError::OperationError(status)),
+        Err(err) => Err(err),
+    };
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(result)));
+}
+
+#[inline(always)]
+pub unsafe extern "C" fn rmw_pod_initial_callback<V>(modification: *const u8, dst: *mut u8)
+where
+    V: Pod + FasterRmw,
+{
+    let modification = std::ptr::read_unaligned(modification as *const V);
+    let initial =
+        panic::catch_unwind(AssertUnwindSafe(|| V::initial(modification))).unwrap_or(modification);
+    std::ptr::write_unaligned(dst as *mut V, initial);
+}
+
+/// Only used for copy updates: `dst` is a new record, so readers never see `current`
+/// partly updated
+#[inline(always)]
+pub unsafe extern "C" fn rmw_pod_callback<V>(
+    current: *const u8,
+    modification: *const u8,
+    dst: *mut u8,
+) where
+    V: Pod + FasterRmw,
+{
+    let current = std::ptr::read_unaligned(current as *const V);
+    let modification = std::ptr::read_unaligned(modification as *const V);
+    let modified =
+        panic::catch_unwind(AssertUnwindSafe(|| current.rmw(modification))).unwrap_or(current);
+    std::ptr::write_unaligned(dst as *mut V, modified);
+}
+
 pub trait FasterRmw: DeserializeOwned + Serialize {
     /// Specify custom Read-Modify-Write logic
     ///
     /// # Example
     /// ```
-    /// use faster_rs::{status, FasterKv, FasterRmw};
+    /// use faster_rs::{status, FasterKv, FasterRmw, ReadResult};
     /// use serde_derive::{Deserialize, Serialize};
     /// use std::sync::mpsc::Receiver;
     ///
@@ -84,8 +367,32 @@ pub trait FasterRmw: DeserializeOwned + Serialize {
     /// let modification = MyU64 { value: 17 };
     /// store.upsert(&key, &value, 1);
     /// store.rmw(&key, &modification, 1);
-    /// let (status, recv): (u8, Receiver<MyU64>) = store.read(&key, 1);
+    /// let (status, recv): (u8, Receiver<ReadResult<MyU64>>) = store.read(&key, 1);
     /// assert!(status == status::OK);
-    /// assert_eq!(recv.recv().unwrap().value, value.value + modification.value);
+    /// let read = recv.recv().unwrap().unwrap().unwrap();
+    /// assert_eq!(read.value, value.value + modification.value);
     fn rmw(&self, modification: Self) -> Self;
+
+    /// The value created by a Read-Modify-Write on a missing key.
+    /// Defaults to the modification itself.
+    fn initial(modification: Self) -> Self
+    where
+        Self: Sized,
+    {
+        modification
+    }
+
+    /// Applies the modification to the value as it is stored, without copying the record.
+    ///
+    /// Return `false` if the modification cannot be applied in place, in which case
+    /// `rmw` is used. An in-place update is only kept if the new value encodes to the
+    /// same number of bytes, so this suits fixed-size values. The record is locked while
+    /// it is updated, so concurrent updates of a key are applied one after the other and
+    /// readers never see a partly updated value.
+    fn rmw_in_place(&mut self, _modification: Self) -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{FasterKey, FasterRmw, FasterValue};
+use crate::{FasterKey, FasterRmw, FasterValue, Pod};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::ops::Add;
@@ -16,6 +16,12 @@ macro_rules! primitive_impl {
             fn rmw(&self, modification: Self) -> Self {
                 $method(*self, modification)
             }
+
+            #[inline]
+            fn rmw_in_place(&mut self, modification: Self) -> bool {
+                *self = $method(*self, modification);
+                true
+            }
         }
     };
 }
@@ -87,3 +93,12 @@ impl<T: Clone + Serialize + DeserializeOwned + Hash + Eq> FasterRmw for HashSet<
         union.cloned().collect()
     }
 }
+
+macro_rules! pod_impl {
+    ($($ty:ident)*) => {
+        $(unsafe impl Pod for $ty {})*
+    };
+}
+pod_impl!(isize i8 i16 i32 i64 i128 usize u8 u16 u32 u64 u128 f32 f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
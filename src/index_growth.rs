@@ -0,0 +1,155 @@
+use crate::util::{BackgroundThread, FasterPtr};
+use crate::IndexDistribution;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// An index growth run by an `IndexGrowthPolicy`
+#[derive(Clone, Debug)]
+pub struct IndexGrowthRecord {
+    /// Load factor that triggered the growth
+    pub load_factor: f64,
+    pub table_size_before: u64,
+    pub table_size_after: u64,
+    /// `false` if FASTER refused to grow the index, e.g. during a checkpoint
+    pub succeeded: bool,
+    pub started: SystemTime,
+    pub finished: SystemTime,
+}
+
+type GrowthHook = Arc<dyn Fn(&IndexGrowthRecord) + Send + Sync>;
+
+/// Configures the background growth of a `FasterKv`'s hash index.
+///
+/// The index is doubled whenever its load factor, as reported by
+/// `IndexDistribution::load_factor`, is above `max_load_factor`.
+#[derive(Clone)]
+pub struct IndexGrowthPolicy {
+    max_load_factor: f64,
+    check_interval: Duration,
+    on_grown: Option<GrowthHook>,
+}
+
+impl IndexGrowthPolicy {
+    pub fn on_load_factor(max_load_factor: f64) -> IndexGrowthPolicy {
+        IndexGrowthPolicy {
+            max_load_factor,
+            check_interval: Duration::from_secs(1),
+            on_grown: None,
+        }
+    }
+
+    /// How often the load factor is checked, once a second by default
+    pub fn with_check_interval(mut self, interval: Duration) -> IndexGrowthPolicy {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Called once the index has been grown, from the thread whose session finished
+    /// the growth by refreshing, or from the growth thread if FASTER refused to grow it
+    pub fn on_grown<F>(mut self, hook: F) -> IndexGrowthPolicy
+    where
+        F: Fn(&IndexGrowthRecord) + Send + Sync + 'static,
+    {
+        self.on_grown = Some(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.max_load_factor > 0.0
+    }
+}
+
+/// Thread growing the index according to an `IndexGrowthPolicy`
+pub(crate) struct IndexGrowthScheduler {
+    thread: BackgroundThread,
+}
+
+impl IndexGrowthScheduler {
+    pub(crate) fn start(
+        faster_t: *mut ffi::faster_t,
+        policy: IndexGrowthPolicy,
+    ) -> IndexGrowthScheduler {
+        let faster_t = FasterPtr(faster_t);
+        let growing = Arc::new(AtomicBool::new(false));
+        let thread =
+            BackgroundThread::start("faster-index-growth", policy.check_interval, move || {
+                // The load factor only drops once a growth has finished
+                if !growing.load(Ordering::SeqCst) {
+                    maybe_grow(&faster_t, &policy, &growing);
+                }
+            });
+        IndexGrowthScheduler { thread }
+    }
+
+    /// Stops the thread, which must happen before the store is destroyed
+    pub(crate) fn stop(&mut self) {
+        self.thread.stop();
+    }
+}
+
+// A growth started by the scheduler, reported once FASTER has finished it
+struct Growth {
+    record: IndexGrowthRecord,
+    on_grown: Option<GrowthHook>,
+    growing: Arc<AtomicBool>,
+}
+
+impl Growth {
+    fn finish(mut self) {
+        self.record.finished = SystemTime::now();
+        if let Some(hook) = &self.on_grown {
+            hook(&self.record);
+        }
+        self.growing.store(false, Ordering::SeqCst);
+    }
+}
+
+fn maybe_grow(faster_t: &FasterPtr, policy: &IndexGrowthPolicy, growing: &Arc<AtomicBool>) {
+    let distribution = unsafe { IndexDistribution::of(faster_t.0) };
+    if !distribution.should_grow(policy.max_load_factor) {
+        return;
+    }
+    let started = SystemTime::now();
+    let growth = Box::new(Growth {
+        record: IndexGrowthRecord {
+            load_factor: distribution.load_factor(),
+            table_size_before: distribution.table_size,
+            table_size_after: distribution.table_size,
+            succeeded: true,
+            started,
+            finished: started,
+        },
+        on_grown: policy.on_grown.clone(),
+        growing: Arc::clone(growing),
+    });
+    growing.store(true, Ordering::SeqCst);
+    let growth_ptr = Box::into_raw(growth);
+    // FASTER only grows the index as sessions refresh, so it is reported from the callback
+    let started = unsafe {
+        ffi::faster_grow_index_with_callback(
+            faster_t.0,
+            Some(index_grown),
+            growth_ptr as *mut libc::c_void,
+            Some(growth_abandoned),
+        )
+    };
+    if !started {
+        let mut growth = unsafe { Box::from_raw(growth_ptr) };
+        growth.record.succeeded = false;
+        growth.finish();
+    }
+}
+
+unsafe extern "C" fn index_grown(context: *mut libc::c_void, new_size: u64) {
+    let mut growth = Box::from_raw(context as *mut Growth);
+    growth.record.table_size_after = new_size;
+    growth.finish();
+}
+
+// The store was destroyed before its index had grown
+unsafe extern "C" fn growth_abandoned(context: *mut libc::c_void) {
+    let growth = Box::from_raw(context as *mut Growth);
+    growth.growing.store(false, Ordering::SeqCst);
+}
@@ -0,0 +1,170 @@
+// Counters and histograms reported through the `metrics` facade when the `metrics`
+// feature is enabled. Without it the timer is empty and every function does nothing.
+
+#[cfg(feature = "metrics")]
+use crate::status::Status;
+use crate::util::CheckPoint;
+use crate::FasterError;
+
+#[cfg(feature = "metrics")]
+use std::convert::TryFrom;
+use std::time::Duration;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+#[cfg(feature = "metrics")]
+const OPERATIONS: &str = "faster_operations_total";
+#[cfg(feature = "metrics")]
+const OPERATION_SECONDS: &str = "faster_operation_seconds";
+#[cfg(feature = "metrics")]
+const COMPLETE_PENDING_SECONDS: &str = "faster_complete_pending_seconds";
+#[cfg(feature = "metrics")]
+const CHECKPOINTS: &str = "faster_checkpoints_total";
+#[cfg(feature = "metrics")]
+const CHECKPOINT_SECONDS: &str = "faster_checkpoint_seconds";
+#[cfg(feature = "metrics")]
+const CHECKPOINT_DURABLE_SECONDS: &str = "faster_checkpoint_durable_seconds";
+#[cfg(feature = "metrics")]
+const RECOVERIES: &str = "faster_recoveries_total";
+#[cfg(feature = "metrics")]
+const RECOVERY_SECONDS: &str = "faster_recovery_seconds";
+
+// Operations are labelled by the FASTER operation they issue, whichever API issued them
+pub(crate) const UPSERT: &str = "upsert";
+pub(crate) const READ: &str = "read";
+pub(crate) const RMW: &str = "rmw";
+pub(crate) const DELETE: &str = "delete";
+
+pub(crate) const FULL: &str = "full";
+pub(crate) const INDEX: &str = "index";
+pub(crate) const HYBRID_LOG: &str = "hybrid_log";
+
+/// Registers descriptions of the metrics reported by faster-rs with the installed
+/// recorder. Only needed by exporters that publish descriptions.
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    metrics::describe_counter!(
+        OPERATIONS,
+        "Operations issued, by operation and the status FASTER returned"
+    );
+    metrics::describe_histogram!(
+        OPERATION_SECONDS,
+        metrics::Unit::Seconds,
+        "Time taken to issue an operation, not including completing it if it went pending"
+    );
+    metrics::describe_histogram!(
+        COMPLETE_PENDING_SECONDS,
+        metrics::Unit::Seconds,
+        "Time spent in complete_pending"
+    );
+    metrics::describe_counter!(CHECKPOINTS, "Checkpoints requested, by kind and result");
+    metrics::describe_histogram!(
+        CHECKPOINT_SECONDS,
+        metrics::Unit::Seconds,
+        "Time taken to start a checkpoint"
+    );
+    metrics::describe_histogram!(
+        CHECKPOINT_DURABLE_SECONDS,
+        metrics::Unit::Seconds,
+        "Time from starting a scheduled checkpoint until it was durable"
+    );
+    metrics::describe_counter!(RECOVERIES, "Recoveries, by result");
+    metrics::describe_histogram!(
+        RECOVERY_SECONDS,
+        metrics::Unit::Seconds,
+        "Time taken to recover a store"
+    );
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Timer {
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+impl Timer {
+    #[inline(always)]
+    pub(crate) fn start() -> Timer {
+        Timer {
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn status_label(status: u8) -> &'static str {
+    match Status::try_from(status) {
+        Ok(Status::Ok) => "ok",
+        Ok(Status::Pending) => "pending",
+        Ok(Status::NotFound) => "not_found",
+        Ok(Status::OutOfMemory) => "out_of_memory",
+        Ok(Status::IoError) => "io_error",
+        Ok(Status::Corruption) => "corruption",
+        Ok(Status::Aborted) => "aborted",
+        Err(_) => "invalid",
+    }
+}
+
+#[inline(always)]
+pub(crate) fn operation(operation: &'static str, status: u8, timer: Timer) {
+    #[cfg(feature = "metrics")]
+    {
+        metrics::counter!(OPERATIONS, "operation" => operation, "status" => status_label(status))
+            .increment(1);
+        metrics::histogram!(OPERATION_SECONDS, "operation" => operation)
+            .record(timer.started.elapsed());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (operation, status, timer);
+}
+
+#[inline(always)]
+pub(crate) fn complete_pending(wait: bool, timer: Timer) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(COMPLETE_PENDING_SECONDS, "wait" => if wait { "true" } else { "false" })
+        .record(timer.started.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = (wait, timer);
+}
+
+#[inline(always)]
+pub(crate) fn checkpoint(
+    kind: &'static str,
+    result: &Result<CheckPoint, FasterError<'static>>,
+    timer: Timer,
+) {
+    #[cfg(feature = "metrics")]
+    {
+        let result = match result {
+            Ok(checkpoint) if checkpoint.checked => "started",
+            Ok(_) => "refused",
+            Err(_) => "failed",
+        };
+        metrics::counter!(CHECKPOINTS, "kind" => kind, "result" => result).increment(1);
+        metrics::histogram!(CHECKPOINT_SECONDS, "kind" => kind).record(timer.started.elapsed());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (kind, result, timer);
+}
+
+#[inline(always)]
+pub(crate) fn checkpoint_durable(kind: &'static str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(CHECKPOINT_DURABLE_SECONDS, "kind" => kind).record(duration);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (kind, duration);
+}
+
+/// `status` is `None` if FASTER could not start recovering
+#[inline(always)]
+pub(crate) fn recovery(status: Option<u8>, timer: Timer) {
+    #[cfg(feature = "metrics")]
+    {
+        let result = status.map_or("failed", status_label);
+        metrics::counter!(RECOVERIES, "result" => result).increment(1);
+        metrics::histogram!(RECOVERY_SECONDS).record(timer.started.elapsed());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (status, timer);
+}
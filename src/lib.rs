@@ -1,50 +1,148 @@
-extern crate bincode;
 extern crate libc;
 extern crate libfaster_sys as ffi;
 
+mod async_ops;
 mod builder;
+mod checkpoint_catalog;
+mod checkpoint_scheduler;
+mod codec;
+mod compaction;
 mod faster_error;
 mod faster_traits;
 mod impls;
+mod index_growth;
+mod instrumentation;
+mod scan;
+mod session;
+mod stats;
 pub mod status;
+mod typed_faster_kv;
 mod util;
 
+pub use crate::async_ops::{CompletionFuture, ReadBatchFuture, ReadFuture, RmwFetchFuture};
 pub use crate::builder::FasterKvBuilder;
+use crate::checkpoint_catalog::read_persisted_sessions;
+pub use crate::checkpoint_catalog::{
+    CheckpointCatalog, CheckpointInfo, CheckpointKind, RetentionPolicy,
+};
+use crate::checkpoint_scheduler::CheckpointScheduler;
+pub use crate::checkpoint_scheduler::{
+    CheckpointMode, CheckpointRecord, CheckpointSchedule, CheckpointTrigger,
+};
+#[cfg(feature = "serde_json")]
+pub use crate::codec::Json;
+#[cfg(feature = "rmp-serde")]
+pub use crate::codec::MessagePack;
+#[cfg(feature = "postcard")]
+pub use crate::codec::Postcard;
+pub use crate::codec::{Bincode, Codec};
+use crate::compaction::CompactionScheduler;
+pub use crate::compaction::{CompactionPolicy, CompactionRecord};
 pub use crate::faster_error::FasterError;
-use crate::faster_traits::{read_callback, rmw_callback};
-pub use crate::faster_traits::{FasterKey, FasterRmw, FasterValue};
+use crate::faster_traits::{
+    read_bytes_callback, read_callback, read_pod_callback, release_context, rmw_bytes_callback,
+    rmw_callback, rmw_in_place_callback, rmw_initial_callback, rmw_pod_callback,
+    rmw_pod_initial_callback, rmw_with_callback, ReadSender,
+};
+pub use crate::faster_traits::{BytesReadResult, FasterKey, FasterRmw, FasterValue, Pod};
+use crate::index_growth::IndexGrowthScheduler;
+pub use crate::index_growth::{IndexGrowthPolicy, IndexGrowthRecord};
+#[cfg(feature = "metrics")]
+pub use crate::instrumentation::describe_metrics;
+use crate::instrumentation::{Timer, DELETE, READ, RMW, UPSERT};
+pub use crate::scan::{LogIter, LogScan};
+pub use crate::session::Session;
+pub use crate::stats::{IndexDistribution, IndexStats, LogStats, ENTRIES_PER_BUCKET};
+pub use crate::status::Status;
+pub use crate::typed_faster_kv::{TypedFasterKv, TypedSession};
 use crate::util::*;
 
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::fs;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver};
+
+/// The outcome of a read: `None` if the key does not exist
+pub type ReadResult<V> = Result<Option<V>, FasterError<'static>>;
+
+/// The outcome of an item of a batch: its status, or the error it failed to encode with
+pub type BatchResult = Result<u8, FasterError<'static>>;
+
+/// The values on either side of a Read-Modify-Write
+#[derive(Clone, Debug, PartialEq)]
+pub struct RmwFetch<V> {
+    /// `None` if the key did not exist
+    pub before: Option<V>,
+    pub after: V,
+}
+
+pub type RmwFetchResult<V> = Result<RmwFetch<V>, FasterError<'static>>;
+
+// Sends the last outcome of an update once FASTER releases the operation
+struct FetchSender<V, S: ReadSender<RmwFetchResult<V>>> {
+    sender: Option<S>,
+    outcome: RefCell<Option<RmwFetch<V>>>,
+}
+
+impl<V, S: ReadSender<RmwFetchResult<V>>> Drop for FetchSender<V, S> {
+    fn drop(&mut self) {
+        // No outcome means the current value or the modification could not be decoded
+        let result = self
+            .outcome
+            .borrow_mut()
+            .take()
+            .ok_or(FasterError::OperationError(Status::Aborted));
+        if let Some(sender) = self.sender.take() {
+            sender.send_value(result);
+        }
+    }
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn deallocate_vec(vec: *mut u8, length: u64) {
     drop(Vec::from_raw_parts(vec, length as usize, length as usize));
 }
 
-pub struct FasterKv {
+/// A FASTER store whose keys and values are encoded with the codec `C`
+pub struct FasterKv<C = Bincode> {
     faster_t: *mut ffi::faster_t,
     storage_dir: Option<String>,
+    checkpoint_scheduler: Option<CheckpointScheduler>,
+    compaction_scheduler: Option<CompactionScheduler>,
+    index_growth_scheduler: Option<IndexGrowthScheduler>,
+    codec: PhantomData<fn() -> C>,
 }
 
-impl FasterKv {
+impl<C: Codec> FasterKv<C> {
     pub fn upsert<K, V>(&self, key: &K, value: &V, monotonic_serial_number: u64) -> u8
     where
         K: FasterKey,
         V: FasterValue,
     {
-        let mut encoded_key = bincode::serialize(key).unwrap();
+        let encoded_key = C::encode(key).unwrap();
+        let encoded_value = C::encode(value).unwrap();
+        self.upsert_encoded(encoded_key, encoded_value, monotonic_serial_number)
+    }
+
+    fn upsert_encoded(
+        &self,
+        mut encoded_key: Vec<u8>,
+        mut encoded_value: Vec<u8>,
+        monotonic_serial_number: u64,
+    ) -> u8 {
         let encoded_key_length = encoded_key.len();
         let encoded_key_ptr = encoded_key.as_mut_ptr();
-        let mut encoded_value = bincode::serialize(value).unwrap();
         let encoded_value_length = encoded_value.len();
         let encoded_value_ptr = encoded_value.as_mut_ptr();
         std::mem::forget(encoded_key);
         std::mem::forget(encoded_value);
-        unsafe {
+        let timer = Timer::start();
+        let status = unsafe {
             ffi::faster_upsert(
                 self.faster_t,
                 encoded_key_ptr,
@@ -53,31 +151,86 @@ impl FasterKv {
                 encoded_value_length as u64,
                 monotonic_serial_number,
             )
-        }
+        };
+        instrumentation::operation(UPSERT, status, timer);
+        status
+    }
+
+    /// The receiver yields `Ok(None)` if the key does not exist and an error if
+    /// the value could not be read or deserialized.
+    pub fn read<K, V>(&self, key: &K, monotonic_serial_number: u64) -> (u8, Receiver<ReadResult<V>>)
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        let (sender, receiver) = channel();
+        let status = self.read_into(key, monotonic_serial_number, sender);
+        (status, receiver)
     }
 
-    pub fn read<K, V>(&self, key: &K, monotonic_serial_number: u64) -> (u8, Receiver<V>)
+    fn read_into<K, V, S>(&self, key: &K, monotonic_serial_number: u64, sender: S) -> u8
     where
         K: FasterKey,
         V: FasterValue,
+        S: ReadSender<ReadResult<V>>,
+    {
+        self.read_encoded(C::encode(key).unwrap(), monotonic_serial_number, sender)
+    }
+
+    fn read_encoded<V, S>(
+        &self,
+        mut encoded_key: Vec<u8>,
+        monotonic_serial_number: u64,
+        sender: S,
+    ) -> u8
+    where
+        V: FasterValue,
+        S: ReadSender<ReadResult<V>>,
     {
-        let mut encoded_key = bincode::serialize(key).unwrap();
         let encoded_key_length = encoded_key.len();
         let encoded_key_ptr = encoded_key.as_mut_ptr();
-        let (sender, receiver) = channel();
-        let sender_ptr: *mut Sender<V> = Box::into_raw(Box::new(sender));
+        let sender_ptr: *mut S = Box::into_raw(Box::new(sender));
         std::mem::forget(encoded_key);
+        let timer = Timer::start();
         let status = unsafe {
             ffi::faster_read(
                 self.faster_t,
                 encoded_key_ptr,
                 encoded_key_length as u64,
                 monotonic_serial_number,
-                Some(read_callback::<V>),
+                Some(read_callback::<V, S, C>),
                 sender_ptr as *mut libc::c_void,
             )
         };
-        (status, receiver)
+        instrumentation::operation(READ, status, timer);
+        status
+    }
+
+    // Reads a key that FASTER copies, leaving the caller free to reuse its buffer
+    pub(crate) fn read_encoded_slice<V, S>(
+        &self,
+        encoded_key: &[u8],
+        monotonic_serial_number: u64,
+        sender: S,
+    ) -> u8
+    where
+        V: FasterValue,
+        S: ReadSender<ReadResult<V>>,
+    {
+        let sender_ptr: *mut S = Box::into_raw(Box::new(sender));
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_read_bytes(
+                self.faster_t,
+                encoded_key.as_ptr(),
+                encoded_key.len() as u64,
+                monotonic_serial_number,
+                Some(read_callback::<V, S, C>),
+                sender_ptr as *mut libc::c_void,
+            )
+        };
+        instrumentation::operation(READ, status, timer);
+        status
     }
 
     pub fn rmw<K, V>(&self, key: &K, value: &V, monotonic_serial_number: u64) -> u8
@@ -85,15 +238,28 @@ impl FasterKv {
         K: FasterKey,
         V: FasterRmw,
     {
-        let mut encoded_key = bincode::serialize(key).unwrap();
+        let encoded_key = C::encode(key).unwrap();
+        let encoded_value = C::encode(value).unwrap();
+        self.rmw_encoded::<V>(encoded_key, encoded_value, monotonic_serial_number)
+    }
+
+    pub(crate) fn rmw_encoded<V>(
+        &self,
+        mut encoded_key: Vec<u8>,
+        mut encoded_value: Vec<u8>,
+        monotonic_serial_number: u64,
+    ) -> u8
+    where
+        V: FasterRmw,
+    {
         let encoded_key_length = encoded_key.len();
         let encoded_key_ptr = encoded_key.as_mut_ptr();
-        let mut encoded_value = bincode::serialize(value).unwrap();
         let encoded_value_length = encoded_value.len();
         let encoded_value_ptr = encoded_value.as_mut_ptr();
         std::mem::forget(encoded_key);
         std::mem::forget(encoded_value);
-        unsafe {
+        let timer = Timer::start();
+        let status = unsafe {
             ffi::faster_rmw(
                 self.faster_t,
                 encoded_key_ptr,
@@ -101,78 +267,530 @@ impl FasterKv {
                 encoded_value_ptr,
                 encoded_value_length as u64,
                 monotonic_serial_number,
-                Some(rmw_callback::<V>),
+                Some(rmw_initial_callback::<V, C>),
+                Some(rmw_in_place_callback::<V, C>),
+                Some(rmw_callback::<V, C>),
             )
-        }
+        };
+        instrumentation::operation(RMW, status, timer);
+        status
     }
 
-    pub fn size(&self) -> u64 {
-        unsafe { ffi::faster_size(self.faster_t) }
+    // Like rmw_encoded, borrowing the encoded key and modification
+    pub(crate) fn rmw_borrowed<V>(
+        &self,
+        encoded_key: &[u8],
+        encoded_value: &[u8],
+        monotonic_serial_number: u64,
+    ) -> u8
+    where
+        V: FasterRmw,
+    {
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_rmw_borrowed(
+                self.faster_t,
+                encoded_key.as_ptr(),
+                encoded_key.len() as u64,
+                encoded_value.as_ptr(),
+                encoded_value.len() as u64,
+                monotonic_serial_number,
+                Some(rmw_initial_callback::<V, C>),
+                Some(rmw_in_place_callback::<V, C>),
+                Some(rmw_callback::<V, C>),
+            )
+        };
+        instrumentation::operation(RMW, status, timer);
+        status
     }
 
-    pub fn checkpoint(&self) -> Result<CheckPoint, FasterError> {
-        if self.storage_dir.is_none() {
-            return Err(FasterError::InvalidType);
-        }
+    /// Read-Modify-Write with the update given as a closure rather than by `FasterRmw`.
+    ///
+    /// `update` receives the current value, or `None` if the key does not exist,
+    /// together with `input`, whose type may differ from the value's. It may be
+    /// called more than once for the same operation, so it must be deterministic.
+    pub fn rmw_with<K, I, V, F>(
+        &self,
+        key: &K,
+        input: &I,
+        monotonic_serial_number: u64,
+        update: F,
+    ) -> u8
+    where
+        K: FasterKey,
+        I: FasterValue,
+        V: FasterValue,
+        F: Fn(Option<&V>, I) -> V + 'static,
+    {
+        self.update_with(
+            key,
+            input,
+            monotonic_serial_number,
+            move |current, input| Some(update(current, input)),
+        )
+    }
 
-        let result = unsafe { ffi::faster_checkpoint(self.faster_t) };
-        match result.is_null() {
-            true => Err(FasterError::CheckpointError),
-            false => {
-                let boxed = unsafe { Box::from_raw(result) }; // makes sure memory is dropped
-                let token_str =
-                    unsafe { CStr::from_ptr((*boxed).token).to_str().unwrap().to_owned() };
+    /// Like `rmw_with`, but the record is left unchanged if `update` returns `None`
+    fn update_with<K, I, V, F>(
+        &self,
+        key: &K,
+        input: &I,
+        monotonic_serial_number: u64,
+        update: F,
+    ) -> u8
+    where
+        K: FasterKey,
+        I: FasterValue,
+        V: FasterValue,
+        F: Fn(Option<&V>, I) -> Option<V> + 'static,
+    {
+        let encoded_key = C::encode(key).unwrap();
+        let encoded_input = C::encode(input).unwrap();
+        self.update_with_encoded(
+            &encoded_key,
+            &encoded_input,
+            monotonic_serial_number,
+            update,
+        )
+    }
 
-                let checkpoint = CheckPoint {
-                    checked: (*boxed).checked,
-                    token: token_str,
+    pub(crate) fn update_with_encoded<I, V, F>(
+        &self,
+        encoded_key: &[u8],
+        encoded_input: &[u8],
+        monotonic_serial_number: u64,
+        update: F,
+    ) -> u8
+    where
+        I: FasterValue,
+        V: FasterValue,
+        F: Fn(Option<&V>, I) -> Option<V> + 'static,
+    {
+        let update_ptr: *mut F = Box::into_raw(Box::new(update));
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_rmw_with(
+                self.faster_t,
+                encoded_key.as_ptr(),
+                encoded_key.len() as u64,
+                encoded_input.as_ptr(),
+                encoded_input.len() as u64,
+                monotonic_serial_number,
+                Some(rmw_with_callback::<V, I, F, C>),
+                update_ptr as *mut libc::c_void,
+                Some(release_context::<F>),
+            )
+        };
+        instrumentation::operation(RMW, status, timer);
+        status
+    }
+
+    /// Read-Modify-Write that also sends the values before and after the update.
+    /// The receiver yields an error if the update could not be applied.
+    pub fn rmw_fetch<K, V>(
+        &self,
+        key: &K,
+        modification: &V,
+        monotonic_serial_number: u64,
+    ) -> (u8, Receiver<RmwFetchResult<V>>)
+    where
+        K: FasterKey,
+        V: FasterRmw + Clone + 'static,
+    {
+        let (sender, receiver) = channel();
+        let status = self.rmw_fetch_into(key, modification, monotonic_serial_number, sender);
+        (status, receiver)
+    }
+
+    fn rmw_fetch_into<K, V, S>(
+        &self,
+        key: &K,
+        modification: &V,
+        monotonic_serial_number: u64,
+        sender: S,
+    ) -> u8
+    where
+        K: FasterKey,
+        V: FasterRmw + Clone + 'static,
+        S: ReadSender<RmwFetchResult<V>> + 'static,
+    {
+        let fetch = FetchSender {
+            sender: Some(sender),
+            outcome: RefCell::new(None),
+        };
+        self.update_with(
+            key,
+            modification,
+            monotonic_serial_number,
+            move |current: Option<&V>, modification: V| {
+                let after = match current {
+                    Some(current) => current.rmw(modification),
+                    None => V::initial(modification),
                 };
-                Ok(checkpoint)
-            }
-        }
+                *fetch.outcome.borrow_mut() = Some(RmwFetch {
+                    before: current.cloned(),
+                    after: after.clone(),
+                });
+                Some(after)
+            },
+        )
     }
 
-    pub fn checkpoint_index(&self) -> Result<CheckPoint, FasterError> {
-        if self.storage_dir.is_none() {
-            return Err(FasterError::InvalidType);
+    /// Atomically replaces the value of `key` with `new` if its current value equals
+    /// `expected`, where `None` expects the key not to exist.
+    ///
+    /// Returns whether the swap happened and the value observed before it. A pending
+    /// operation is completed before returning.
+    pub fn compare_and_swap<K, V>(
+        &self,
+        key: &K,
+        expected: Option<&V>,
+        new: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<(bool, Option<V>), FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue + PartialEq + Clone + 'static,
+    {
+        // Callbacks run on this thread, either now or from complete_pending
+        let outcome = Rc::new(RefCell::new(None));
+        let observed = Rc::clone(&outcome);
+        let expected = expected.cloned();
+        let status = self.update_with(
+            key,
+            new,
+            monotonic_serial_number,
+            move |current: Option<&V>, new: V| {
+                let swapped = current == expected.as_ref();
+                *observed.borrow_mut() = Some((swapped, current.cloned()));
+                match swapped {
+                    true => Some(new),
+                    false => None,
+                }
+            },
+        );
+        if Status::try_from(status)?.into_result()? == Status::Pending {
+            self.complete_pending(true);
         }
+        let outcome = outcome.borrow_mut().take();
+        outcome.ok_or(FasterError::OperationError(Status::Aborted))
+    }
 
-        let result = unsafe { ffi::faster_checkpoint(self.faster_t) };
-        match result.is_null() {
-            true => Err(FasterError::CheckpointError),
-            false => {
-                let boxed = unsafe { Box::from_raw(result) }; // makes sure memory is dropped
-                let token_str =
-                    unsafe { CStr::from_ptr((*boxed).token).to_str().unwrap().to_owned() };
+    pub fn delete<K>(&self, key: &K, monotonic_serial_number: u64) -> u8
+    where
+        K: FasterKey,
+    {
+        self.delete_encoded(C::encode(key).unwrap(), monotonic_serial_number)
+    }
 
-                let checkpoint = CheckPoint {
-                    checked: (*boxed).checked,
-                    token: token_str,
-                };
-                Ok(checkpoint)
-            }
-        }
+    fn delete_encoded(&self, mut encoded_key: Vec<u8>, monotonic_serial_number: u64) -> u8 {
+        let encoded_key_length = encoded_key.len();
+        let encoded_key_ptr = encoded_key.as_mut_ptr();
+        std::mem::forget(encoded_key);
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_delete(
+                self.faster_t,
+                encoded_key_ptr,
+                encoded_key_length as u64,
+                monotonic_serial_number,
+            )
+        };
+        instrumentation::operation(DELETE, status, timer);
+        status
+    }
+
+    /// Like `upsert`, but an error is returned if the key or value cannot be encoded
+    /// or FASTER reports a failure status
+    pub fn try_upsert<K, V>(
+        &self,
+        key: &K,
+        value: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<Status, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        let encoded_key = C::encode(key)?;
+        let encoded_value = C::encode(value)?;
+        let status = self.upsert_encoded(encoded_key, encoded_value, monotonic_serial_number);
+        Status::try_from(status)?.into_result()
     }
 
+    pub fn try_read<K, V>(
+        &self,
+        key: &K,
+        monotonic_serial_number: u64,
+    ) -> Result<(Status, Receiver<ReadResult<V>>), FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        let (sender, receiver) = channel();
+        let status = self.read_encoded(C::encode(key)?, monotonic_serial_number, sender);
+        let status = Status::try_from(status)?.into_result()?;
+        Ok((status, receiver))
+    }
+
+    pub fn try_rmw<K, V>(
+        &self,
+        key: &K,
+        value: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<Status, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterRmw,
+    {
+        let encoded_key = C::encode(key)?;
+        let encoded_value = C::encode(value)?;
+        let status = self.rmw_encoded::<V>(encoded_key, encoded_value, monotonic_serial_number);
+        Status::try_from(status)?.into_result()
+    }
+
+    pub fn try_delete<K>(
+        &self,
+        key: &K,
+        monotonic_serial_number: u64,
+    ) -> Result<Status, FasterError<'static>>
+    where
+        K: FasterKey,
+    {
+        let status = self.delete_encoded(C::encode(key)?, monotonic_serial_number);
+        Status::try_from(status)?.into_result()
+    }
+
+    /// Upserts a key and value that are stored as-is, without going through the codec.
+    /// FASTER copies both slices before returning.
+    pub fn upsert_bytes(&self, key: &[u8], value: &[u8], monotonic_serial_number: u64) -> u8 {
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_upsert_bytes(
+                self.faster_t,
+                key.as_ptr(),
+                key.len() as u64,
+                value.as_ptr(),
+                value.len() as u64,
+                monotonic_serial_number,
+            )
+        };
+        instrumentation::operation(UPSERT, status, timer);
+        status
+    }
+
+    /// Reads the raw bytes stored for `key`. The callback borrows the value from
+    /// FASTER's log, so it must copy anything it wants to keep.
+    ///
+    /// If the read goes pending the callback is called from `complete_pending`.
+    pub fn read_bytes<F>(&self, key: &[u8], monotonic_serial_number: u64, callback: F) -> u8
+    where
+        F: FnOnce(BytesReadResult) + 'static,
+    {
+        let callback_ptr: *mut F = Box::into_raw(Box::new(callback));
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_read_bytes(
+                self.faster_t,
+                key.as_ptr(),
+                key.len() as u64,
+                monotonic_serial_number,
+                Some(read_bytes_callback::<F>),
+                callback_ptr as *mut libc::c_void,
+            )
+        };
+        instrumentation::operation(READ, status, timer);
+        status
+    }
+
+    /// Read-Modify-Write on raw bytes. `merge` is given the current value and
+    /// `modification` and returns the new value; if the key does not exist
+    /// `modification` is stored as the initial value.
+    ///
+    /// `merge` may be called more than once for the same update, so it must be deterministic.
+    /// It is dropped once the operation has finished, which is from `complete_pending`
+    /// if the operation went pending.
+    pub fn rmw_bytes<F>(
+        &self,
+        key: &[u8],
+        modification: &[u8],
+        monotonic_serial_number: u64,
+        merge: F,
+    ) -> u8
+    where
+        F: Fn(&[u8], &[u8]) -> Vec<u8> + 'static,
+    {
+        let merge_ptr: *mut F = Box::into_raw(Box::new(merge));
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_rmw_bytes(
+                self.faster_t,
+                key.as_ptr(),
+                key.len() as u64,
+                modification.as_ptr(),
+                modification.len() as u64,
+                monotonic_serial_number,
+                Some(rmw_bytes_callback::<F>),
+                merge_ptr as *mut libc::c_void,
+                Some(release_context::<F>),
+            )
+        };
+        instrumentation::operation(RMW, status, timer);
+        status
+    }
+
+    /// Upserts a fixed-size record holding `key` and `value` as they are laid out in memory.
+    ///
+    /// Records written by the `_pod` operations have a different layout to those
+    /// written by `upsert`, so a key must always be accessed through the same kind of operation.
+    pub fn upsert_pod<K, V>(&self, key: &K, value: &V, monotonic_serial_number: u64) -> u8
+    where
+        K: Pod,
+        V: Pod,
+    {
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_upsert_pod(
+                self.faster_t,
+                key as *const K as *const u8,
+                mem::size_of::<K>() as u64,
+                value as *const V as *const u8,
+                mem::size_of::<V>() as u64,
+                monotonic_serial_number,
+            )
+        };
+        instrumentation::operation(UPSERT, status, timer);
+        status
+    }
+
+    /// Reads a record written by `upsert_pod` or `rmw_pod`. If the read goes pending
+    /// the callback is called from `complete_pending`.
+    pub fn read_pod<K, V, F>(&self, key: &K, monotonic_serial_number: u64, callback: F) -> u8
+    where
+        K: Pod,
+        V: Pod,
+        F: FnOnce(ReadResult<V>) + 'static,
+    {
+        // Boxing a closure that captures nothing does not allocate
+        let callback_ptr: *mut F = Box::into_raw(Box::new(callback));
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_read_pod(
+                self.faster_t,
+                key as *const K as *const u8,
+                mem::size_of::<K>() as u64,
+                mem::size_of::<V>() as u64,
+                monotonic_serial_number,
+                Some(read_pod_callback::<V, F>),
+                callback_ptr as *mut libc::c_void,
+            )
+        };
+        instrumentation::operation(READ, status, timer);
+        status
+    }
+
+    pub fn rmw_pod<K, V>(&self, key: &K, modification: &V, monotonic_serial_number: u64) -> u8
+    where
+        K: Pod,
+        V: Pod + FasterRmw,
+    {
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_rmw_pod(
+                self.faster_t,
+                key as *const K as *const u8,
+                mem::size_of::<K>() as u64,
+                modification as *const V as *const u8,
+                mem::size_of::<V>() as u64,
+                monotonic_serial_number,
+                Some(rmw_pod_initial_callback::<V>),
+                Some(rmw_pod_callback::<V>),
+            )
+        };
+        instrumentation::operation(RMW, status, timer);
+        status
+    }
+
+    pub fn delete_pod<K>(&self, key: &K, monotonic_serial_number: u64) -> u8
+    where
+        K: Pod,
+    {
+        let timer = Timer::start();
+        let status = unsafe {
+            ffi::faster_delete_pod(
+                self.faster_t,
+                key as *const K as *const u8,
+                mem::size_of::<K>() as u64,
+                monotonic_serial_number,
+            )
+        };
+        instrumentation::operation(DELETE, status, timer);
+        status
+    }
+
+    pub fn size(&self) -> u64 {
+        unsafe { ffi::faster_size(self.faster_t) }
+    }
+
+    /// Iterates over the live records as encoded keys and values, walking the
+    /// hybrid log from head to tail. Superseded versions and deleted keys are skipped.
+    pub fn scan(&self) -> LogScan<'_, C> {
+        LogScan::new(self)
+    }
+
+    /// Like `scan`, but decodes each record as a `K` and a `V`
+    pub fn iter<K, V>(&self) -> LogIter<'_, K, V, C>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        LogIter::new(self.scan())
+    }
+
+    /// Checkpoints both the hash index and the hybrid log
+    pub fn checkpoint(&self) -> Result<CheckPoint, FasterError> {
+        self.take_checkpoint(ffi::faster_checkpoint, instrumentation::FULL)
+    }
+
+    /// Checkpoints only the hash index. Recovery also requires a later hybrid log checkpoint.
+    pub fn checkpoint_index(&self) -> Result<CheckPoint, FasterError> {
+        self.take_checkpoint(ffi::faster_checkpoint_index, instrumentation::INDEX)
+    }
+
+    /// Checkpoints only the hybrid log, which is much cheaper than a full checkpoint.
+    /// It can be recovered together with any earlier index checkpoint.
     pub fn checkpoint_hybrid_log(&self) -> Result<CheckPoint, FasterError> {
+        self.take_checkpoint(
+            ffi::faster_checkpoint_hybrid_log,
+            instrumentation::HYBRID_LOG,
+        )
+    }
+
+    fn take_checkpoint(
+        &self,
+        checkpoint_fn: CheckpointFn,
+        kind: &'static str,
+    ) -> Result<CheckPoint, FasterError> {
         if self.storage_dir.is_none() {
             return Err(FasterError::InvalidType);
         }
+        unsafe { take_checkpoint(self.faster_t, checkpoint_fn, kind) }
+    }
 
-        let result = unsafe { ffi::faster_checkpoint(self.faster_t) };
-        match result.is_null() {
-            true => Err(FasterError::CheckpointError),
-            false => {
-                let boxed = unsafe { Box::from_raw(result) }; // makes sure memory is dropped
-                let token_str =
-                    unsafe { CStr::from_ptr((*boxed).token).to_str().unwrap().to_owned() };
+    /// Checkpoints recently taken by the scheduler configured with
+    /// `FasterKvBuilder::with_checkpoint_schedule`, oldest first
+    pub fn checkpoint_history(&self) -> Vec<CheckpointRecord> {
+        match &self.checkpoint_scheduler {
+            None => Vec::new(),
+            Some(scheduler) => scheduler.history(),
+        }
+    }
 
-                let checkpoint = CheckPoint {
-                    checked: (*boxed).checked,
-                    token: token_str,
-                };
-                Ok(checkpoint)
-            }
+    /// Catalog of the checkpoints in this store's storage directory
+    pub fn checkpoint_catalog(&self) -> Result<CheckpointCatalog, FasterError> {
+        match &self.storage_dir {
+            None => Err(FasterError::InvalidType),
+            Some(dir) => Ok(CheckpointCatalog::new(dir)),
         }
     }
 
@@ -180,16 +798,30 @@ impl FasterKv {
         &self,
         index_token: String,
         hybrid_log_token: String,
-    ) -> Result<Recover, FasterError> {
-        if self.storage_dir.is_none() {
-            return Err(FasterError::InvalidType);
-        }
+    ) -> Result<Recover, FasterError<'static>> {
+        let storage_dir = match &self.storage_dir {
+            None => return Err(FasterError::InvalidType),
+            Some(dir) => dir,
+        };
+        // The serial numbers are only reported, so recovery goes ahead without them
+        let persisted_sessions = match read_persisted_sessions(storage_dir, &hybrid_log_token) {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                eprintln!(
+                    "warning: unable to read the sessions of hybrid log checkpoint {}, \
+                     recovering without them: {}",
+                    hybrid_log_token, err
+                );
+                Vec::new()
+            }
+        };
         let index_token_c = CString::new(index_token).unwrap();
         let index_token_ptr = index_token_c.into_raw();
 
         let hybrid_token_c = CString::new(hybrid_log_token).unwrap();
         let hybrid_token_ptr = hybrid_token_c.into_raw();
 
+        let timer = Timer::start();
         let recover_result = unsafe {
             let rec = ffi::faster_recover(self.faster_t, index_token_ptr, hybrid_token_ptr);
             let _ = CString::from_raw(index_token_ptr);
@@ -198,53 +830,72 @@ impl FasterKv {
         };
 
         match recover_result.is_null() {
-            true => Err(FasterError::RecoveryError),
+            true => {
+                instrumentation::recovery(None, timer);
+                Err(FasterError::RecoveryError)
+            }
             false => {
-                let boxed = unsafe { Box::from_raw(recover_result) }; // makes sure mem is freed
-                let sessions_count = (*boxed).session_ids_count;
+                let result = unsafe { &*recover_result };
+                let sessions_count = result.session_ids_count;
                 let mut session_ids_vec: Vec<String> = Vec::new();
                 for i in 0..sessions_count {
                     let id = unsafe {
-                        CStr::from_ptr(((*boxed).session_ids).offset(37 * i as isize))
+                        CStr::from_ptr((result.session_ids).offset(37 * i as isize))
                             .to_str()
                             .unwrap()
                             .to_owned()
                     };
                     session_ids_vec.push(id);
                 }
+                let sessions = persisted_sessions
+                    .into_iter()
+                    .filter(|(id, _)| session_ids_vec.contains(id))
+                    .collect();
                 let recover = Recover {
-                    status: (*boxed).status,
-                    version: (*boxed).version,
+                    status: result.status,
+                    version: result.version,
                     session_ids: session_ids_vec,
+                    sessions,
                 };
+                unsafe { ffi::faster_recover_result_free(recover_result) };
+                instrumentation::recovery(Some(recover.status), timer);
                 Ok(recover)
             }
         }
     }
 
     pub fn complete_pending(&self, b: bool) -> () {
-        unsafe { ffi::faster_complete_pending(self.faster_t, b) }
+        self.try_complete_pending(b);
     }
 
-    pub fn start_session(&self) -> String {
-        unsafe {
+    // Returns true once none of this thread's operations remain pending
+    pub(crate) fn try_complete_pending(&self, wait: bool) -> bool {
+        let timer = Timer::start();
+        let completed = unsafe { ffi::faster_complete_pending(self.faster_t, wait) };
+        instrumentation::complete_pending(wait, timer);
+        completed
+    }
+
+    pub fn start_session(&self) -> Session<'_, C> {
+        let id = unsafe {
             let c_guid = ffi::faster_start_session(self.faster_t);
-            let rust_str = CStr::from_ptr(c_guid).to_str().unwrap().to_owned();
-            rust_str
-        }
+            CStr::from_ptr(c_guid).to_str().unwrap().to_owned()
+        };
+        Session::new(self, id, 0)
     }
 
-    pub fn continue_session(&self, token: String) -> u64 {
-        let token_str = CString::new(token).unwrap();
+    pub fn continue_session(&self, token: String) -> Session<'_, C> {
+        let token_str = CString::new(token.clone()).unwrap();
         let token_ptr = token_str.into_raw();
-        unsafe {
+        let serial_number = unsafe {
             let result = ffi::faster_continue_session(self.faster_t, token_ptr);
             let _ = CString::from_raw(token_ptr);
             result
-        }
+        };
+        Session::new(self, token, serial_number)
     }
 
-    pub fn stop_session(&self) -> () {
+    fn stop_session(&self) -> () {
         unsafe { ffi::faster_stop_session(self.faster_t) }
     }
 
@@ -254,16 +905,69 @@ impl FasterKv {
         }
     }
 
+    /// Prints the distribution of the hash index to stdout, see `index_distribution`
     pub fn dump_distribution(&self) -> () {
         unsafe {
             ffi::faster_dump_distribution(self.faster_t);
         }
     }
 
+    /// How the entries of the hash index are spread over its buckets
+    pub fn index_distribution(&self) -> IndexDistribution {
+        unsafe { IndexDistribution::of(self.faster_t) }
+    }
+
+    /// Address of the oldest record still in the hybrid log
+    pub fn log_begin_address(&self) -> u64 {
+        unsafe { ffi::faster_log_begin_address(self.faster_t) }
+    }
+
+    /// Address the next record will be appended at
+    pub fn log_tail_address(&self) -> u64 {
+        unsafe { ffi::faster_log_tail_address(self.faster_t) }
+    }
+
+    /// Address below which records are no longer updated in place, and which the log
+    /// may be compacted up to
+    pub fn log_safe_read_only_address(&self) -> u64 {
+        unsafe { ffi::faster_log_safe_read_only_address(self.faster_t) }
+    }
+
+    /// Addresses and sizes of the hybrid log's regions, together with the occupancy of
+    /// the hash index
+    pub fn log_stats(&self) -> LogStats {
+        unsafe {
+            let mut stats: ffi::faster_log_stats = mem::zeroed();
+            ffi::faster_get_log_stats(self.faster_t, &mut stats);
+            LogStats::new(&stats)
+        }
+    }
+
+    /// Compacts the hybrid log up to `until_address`.
+    ///
+    /// Live records before `until_address` are copied to the tail of the log and
+    /// the begin address is moved to `until_address`, so the space taken by
+    /// superseded and deleted records can be reclaimed. `until_address` must not
+    /// lie in the mutable region of the log.
+    pub fn compact(&self, until_address: u64) -> Result<(), FasterError<'static>> {
+        match unsafe { ffi::faster_compact(self.faster_t, until_address) } {
+            true => Ok(()),
+            false => Err(FasterError::CompactionError),
+        }
+    }
+
+    /// Starts doubling the hash index, returning false if FASTER refused to. The
+    /// index has grown once every session has refreshed.
     pub fn grow_index(&self) -> bool {
         unsafe { ffi::faster_grow_index(self.faster_t) }
     }
 
+    /// Grows the index if its load factor is above `max_load_factor`, returning
+    /// whether it started growing
+    pub fn grow_index_if_needed(&self, max_load_factor: f64) -> bool {
+        self.index_distribution().should_grow(max_load_factor) && self.grow_index()
+    }
+
     // Warning: Calling this will remove the stored data
     pub fn clean_storage(&self) -> Result<(), FasterError> {
         match &self.storage_dir {
@@ -274,12 +978,6 @@ impl FasterKv {
             }
         }
     }
-
-    fn destroy(&self) -> () {
-        unsafe {
-            ffi::faster_destroy(self.faster_t);
-        }
-    }
 }
 
 impl Default for FasterKv {
@@ -291,11 +989,23 @@ impl Default for FasterKv {
 }
 
 // In order to make sure we release the resources the C interface has allocated for the store
-impl Drop for FasterKv {
+impl<C> Drop for FasterKv<C> {
     fn drop(&mut self) {
-        self.destroy();
+        // The schedulers must not touch a destroyed store
+        if let Some(scheduler) = &mut self.checkpoint_scheduler {
+            scheduler.stop();
+        }
+        if let Some(scheduler) = &mut self.compaction_scheduler {
+            scheduler.stop();
+        }
+        if let Some(scheduler) = &mut self.index_growth_scheduler {
+            scheduler.stop();
+        }
+        unsafe {
+            ffi::faster_destroy(self.faster_t);
+        }
     }
 }
 
-unsafe impl Send for FasterKv {}
-unsafe impl Sync for FasterKv {}
+unsafe impl<C> Send for FasterKv<C> {}
+unsafe impl<C> Sync for FasterKv<C> {}
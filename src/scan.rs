@@ -0,0 +1,149 @@
+extern crate libfaster_sys as ffi;
+
+use crate::{Codec, FasterError, FasterKey, FasterKv, FasterValue};
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// Walks the records of the hybrid log between two addresses, oldest first
+struct LogCursor {
+    scan: *mut ffi::faster_scan_t,
+}
+
+// A record as stored in the log, which may since have been superseded
+struct LogRecord<'c> {
+    key: &'c [u8],
+    value: &'c [u8],
+    address: u64,
+    deleted: bool,
+}
+
+impl LogCursor {
+    fn new<C: Codec>(store: &FasterKv<C>, from_address: u64, until_address: u64) -> Self {
+        let scan = unsafe { ffi::faster_scan_begin(store.faster_t, from_address, until_address) };
+        LogCursor { scan }
+    }
+
+    // The returned slices are only valid until the next call
+    fn next_record(&mut self) -> Option<LogRecord<'_>> {
+        let mut key = std::ptr::null();
+        let mut key_length = 0;
+        let mut value = std::ptr::null();
+        let mut value_length = 0;
+        let mut address = 0;
+        let mut deleted = false;
+        let found = unsafe {
+            ffi::faster_scan_next(
+                self.scan,
+                &mut key,
+                &mut key_length,
+                &mut value,
+                &mut value_length,
+                &mut address,
+                &mut deleted,
+            )
+        };
+        if !found {
+            return None;
+        }
+        unsafe {
+            Some(LogRecord {
+                key: std::slice::from_raw_parts(key, key_length as usize),
+                value: std::slice::from_raw_parts(value, value_length as usize),
+                address,
+                deleted,
+            })
+        }
+    }
+}
+
+impl Drop for LogCursor {
+    fn drop(&mut self) {
+        unsafe { ffi::faster_scan_end(self.scan) }
+    }
+}
+
+/// Iterator over the live records of a `FasterKv`, as raw encoded keys and values.
+///
+/// Only the latest version of each key is returned, and deleted keys are skipped.
+/// Records appended after the scan was created are not visited, so records that a
+/// compaction running meanwhile copies to the tail of the log may be missed.
+pub struct LogScan<'a, C: Codec> {
+    cursor: LogCursor,
+    // Address of the latest version of every live key
+    live: HashMap<Vec<u8>, u64>,
+    store: PhantomData<&'a FasterKv<C>>,
+}
+
+impl<'a, C: Codec> LogScan<'a, C> {
+    pub(crate) fn new(store: &'a FasterKv<C>) -> Self {
+        let (from_address, until_address) = unsafe {
+            (
+                ffi::faster_log_begin_address(store.faster_t),
+                ffi::faster_log_tail_address(store.faster_t),
+            )
+        };
+
+        // The first pass finds the latest version of each key, keeping only their addresses,
+        // and releasing its iterator before the second pass starts
+        let mut live = HashMap::new();
+        {
+            let mut cursor = LogCursor::new(store, from_address, until_address);
+            while let Some(record) = cursor.next_record() {
+                if record.deleted {
+                    live.remove(record.key);
+                } else {
+                    live.insert(record.key.to_vec(), record.address);
+                }
+            }
+        }
+
+        LogScan {
+            cursor: LogCursor::new(store, from_address, until_address),
+            live,
+            store: PhantomData,
+        }
+    }
+}
+
+impl<'a, C: Codec> Iterator for LogScan<'a, C> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(record) = self.cursor.next_record() {
+            if !record.deleted && self.live.get(record.key) == Some(&record.address) {
+                return Some((record.key.to_vec(), record.value.to_vec()));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the live records of a `FasterKv`, decoded with its codec
+pub struct LogIter<'a, K, V, C: Codec> {
+    scan: LogScan<'a, C>,
+    types: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, K, V, C: Codec> LogIter<'a, K, V, C> {
+    pub(crate) fn new(scan: LogScan<'a, C>) -> Self {
+        LogIter {
+            scan,
+            types: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V, C> Iterator for LogIter<'a, K, V, C>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    type Item = Result<(K, V), FasterError<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.scan.next()?;
+        Some(C::decode(&key).and_then(|key| Ok((key, C::decode(&value)?))))
+    }
+}
@@ -0,0 +1,444 @@
+use crate::faster_traits::IndexedSender;
+use crate::{
+    BatchResult, Bincode, BytesReadResult, Codec, CompletionFuture, FasterError, FasterKey,
+    FasterKv, FasterRmw, FasterValue, Pod, ReadBatchFuture, ReadFuture, ReadResult, RmwFetchFuture,
+    RmwFetchResult,
+};
+
+use futures::channel::oneshot;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::mpsc::{channel, Receiver};
+
+/// Number of operations a batch issues between refreshes of the session
+const BATCH_REFRESH_INTERVAL: usize = 64;
+
+/// A FASTER session registered to the current thread.
+///
+/// Each operation is given the next monotonic serial number of the session, which
+/// CPR uses to report which operations were persisted by a checkpoint.
+///
+/// Dropping the session completes any pending operations and stops it. Sessions
+/// are bound to the thread that started them, so `Session` is neither `Send` nor `Sync`.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<faster_rs::Session>();
+/// ```
+pub struct Session<'a, C: Codec = Bincode> {
+    store: &'a FasterKv<C>,
+    id: String,
+    persisted_serial_number: u64,
+    serial_number: Cell<u64>,
+    // Raw pointers are !Send and !Sync
+    thread_bound: PhantomData<*const ()>,
+}
+
+impl<'a, C: Codec> Session<'a, C> {
+    pub(crate) fn new(store: &'a FasterKv<C>, id: String, persisted_serial_number: u64) -> Self {
+        Session {
+            store,
+            id,
+            persisted_serial_number,
+            serial_number: Cell::new(persisted_serial_number),
+            thread_bound: PhantomData,
+        }
+    }
+
+    /// The GUID identifying this session, used to continue it after recovery
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The last serial number persisted by a checkpoint before this session was
+    /// continued, or 0 for a newly started session
+    pub fn persisted_serial_number(&self) -> u64 {
+        self.persisted_serial_number
+    }
+
+    /// The most recent serial number used by this session
+    pub fn serial_number(&self) -> u64 {
+        self.serial_number.get()
+    }
+
+    fn next_serial_number(&self) -> u64 {
+        let next = self.serial_number.get() + 1;
+        self.serial_number.set(next);
+        next
+    }
+
+    fn set_serial_number(&self, monotonic_serial_number: u64) -> Result<(), FasterError<'static>> {
+        let last = self.serial_number.get();
+        if monotonic_serial_number <= last {
+            return Err(FasterError::SerialNumberError(
+                monotonic_serial_number,
+                last,
+            ));
+        }
+        self.serial_number.set(monotonic_serial_number);
+        Ok(())
+    }
+
+    pub fn upsert<K, V>(&self, key: &K, value: &V) -> u8
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        self.store.upsert(key, value, self.next_serial_number())
+    }
+
+    pub fn read<K, V>(&self, key: &K) -> (u8, Receiver<ReadResult<V>>)
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        self.store.read(key, self.next_serial_number())
+    }
+
+    pub fn rmw<K, V>(&self, key: &K, value: &V) -> u8
+    where
+        K: FasterKey,
+        V: FasterRmw,
+    {
+        self.store.rmw(key, value, self.next_serial_number())
+    }
+
+    pub fn rmw_with<K, I, V, F>(&self, key: &K, input: &I, update: F) -> u8
+    where
+        K: FasterKey,
+        I: FasterValue,
+        V: FasterValue,
+        F: Fn(Option<&V>, I) -> V + 'static,
+    {
+        self.store
+            .rmw_with(key, input, self.next_serial_number(), update)
+    }
+
+    pub fn rmw_fetch<K, V>(&self, key: &K, modification: &V) -> (u8, Receiver<RmwFetchResult<V>>)
+    where
+        K: FasterKey,
+        V: FasterRmw + Clone + 'static,
+    {
+        self.store
+            .rmw_fetch(key, modification, self.next_serial_number())
+    }
+
+    pub fn rmw_fetch_async<K, V>(&self, key: &K, modification: &V) -> RmwFetchFuture<'_, 'a, V, C>
+    where
+        K: FasterKey,
+        V: FasterRmw + Clone + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let status =
+            self.store
+                .rmw_fetch_into(key, modification, self.next_serial_number(), sender);
+        RmwFetchFuture::new(self, status, receiver)
+    }
+
+    pub fn compare_and_swap<K, V>(
+        &self,
+        key: &K,
+        expected: Option<&V>,
+        new: &V,
+    ) -> Result<(bool, Option<V>), FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue + PartialEq + Clone + 'static,
+    {
+        self.store
+            .compare_and_swap(key, expected, new, self.next_serial_number())
+    }
+
+    pub fn delete<K>(&self, key: &K) -> u8
+    where
+        K: FasterKey,
+    {
+        self.store.delete(key, self.next_serial_number())
+    }
+
+    pub fn upsert_bytes(&self, key: &[u8], value: &[u8]) -> u8 {
+        self.store
+            .upsert_bytes(key, value, self.next_serial_number())
+    }
+
+    pub fn read_bytes<F>(&self, key: &[u8], callback: F) -> u8
+    where
+        F: FnOnce(BytesReadResult) + 'static,
+    {
+        self.store
+            .read_bytes(key, self.next_serial_number(), callback)
+    }
+
+    pub fn rmw_bytes<F>(&self, key: &[u8], modification: &[u8], merge: F) -> u8
+    where
+        F: Fn(&[u8], &[u8]) -> Vec<u8> + 'static,
+    {
+        self.store
+            .rmw_bytes(key, modification, self.next_serial_number(), merge)
+    }
+
+    pub fn upsert_pod<K, V>(&self, key: &K, value: &V) -> u8
+    where
+        K: Pod,
+        V: Pod,
+    {
+        self.store.upsert_pod(key, value, self.next_serial_number())
+    }
+
+    pub fn read_pod<K, V, F>(&self, key: &K, callback: F) -> u8
+    where
+        K: Pod,
+        V: Pod,
+        F: FnOnce(ReadResult<V>) + 'static,
+    {
+        self.store
+            .read_pod(key, self.next_serial_number(), callback)
+    }
+
+    pub fn rmw_pod<K, V>(&self, key: &K, modification: &V) -> u8
+    where
+        K: Pod,
+        V: Pod + FasterRmw,
+    {
+        self.store
+            .rmw_pod(key, modification, self.next_serial_number())
+    }
+
+    pub fn delete_pod<K>(&self, key: &K) -> u8
+    where
+        K: Pod,
+    {
+        self.store.delete_pod(key, self.next_serial_number())
+    }
+
+    /// Read whose value is awaited rather than received from a channel
+    pub fn read_async<K, V>(&self, key: &K) -> ReadFuture<'_, 'a, V, C>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let status = self.store.read_into(key, self.next_serial_number(), sender);
+        ReadFuture::new(self, status, receiver)
+    }
+
+    pub fn upsert_async<K, V>(&self, key: &K, value: &V) -> CompletionFuture<'_, 'a, C>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        CompletionFuture::new(self, self.upsert(key, value))
+    }
+
+    pub fn rmw_async<K, V>(&self, key: &K, value: &V) -> CompletionFuture<'_, 'a, C>
+    where
+        K: FasterKey,
+        V: FasterRmw,
+    {
+        CompletionFuture::new(self, self.rmw(key, value))
+    }
+
+    /// Upserts every pair in order, returning the status of each.
+    ///
+    /// Keys and values are encoded into a single buffer reused for the whole
+    /// batch, and the session is refreshed periodically while it runs. A pair that
+    /// fails to encode is reported as an error in its place and never reaches the store.
+    pub fn upsert_batch<'i, K, V, I>(&self, items: I) -> Vec<BatchResult>
+    where
+        K: FasterKey + 'i,
+        V: FasterValue + 'i,
+        I: IntoIterator<Item = (&'i K, &'i V)>,
+    {
+        let mut buffer = Vec::new();
+        self.run_batch(items, |(key, value), monotonic_serial_number| {
+            let key_length = encode_pair::<K, V, C>(&mut buffer, key, value)?;
+            let (encoded_key, encoded_value) = buffer.split_at(key_length);
+            Ok(self
+                .store
+                .upsert_bytes(encoded_key, encoded_value, monotonic_serial_number))
+        })
+    }
+
+    /// Reads every key in order, returning the status of each together with
+    /// one receiver for all of the values.
+    ///
+    /// Each value arrives tagged with the position of its key in the batch. Reads
+    /// that go pending arrive once `complete_pending` is called. A key that fails
+    /// to encode is reported as an error in its place and nothing arrives for it.
+    pub fn read_batch<'i, K, V, I>(
+        &self,
+        keys: I,
+    ) -> (Vec<BatchResult>, Receiver<(usize, ReadResult<V>)>)
+    where
+        K: FasterKey + 'i,
+        V: FasterValue,
+        I: IntoIterator<Item = &'i K>,
+    {
+        let (sender, receiver) = channel();
+        let mut buffer = Vec::new();
+        let mut index = 0;
+        let statuses = self.run_batch(keys, |key, monotonic_serial_number| {
+            let sender = IndexedSender {
+                index,
+                sender: sender.clone(),
+            };
+            index += 1;
+            buffer.clear();
+            C::encode_into(key, &mut buffer)?;
+            Ok(self
+                .store
+                .read_encoded_slice(&buffer, monotonic_serial_number, sender))
+        });
+        (statuses, receiver)
+    }
+
+    /// Batch of reads whose values are awaited together, in the order of `keys`
+    pub fn read_batch_async<'i, K, V, I>(&self, keys: I) -> ReadBatchFuture<'_, 'a, V, C>
+    where
+        K: FasterKey + 'i,
+        V: FasterValue,
+        I: IntoIterator<Item = &'i K>,
+    {
+        let (statuses, receiver) = self.read_batch(keys);
+        ReadBatchFuture::new(self, statuses, receiver)
+    }
+
+    /// Applies every modification in order with `FasterRmw`, returning the status of each.
+    ///
+    /// Each item goes through the same initial, in-place and copy updates as `rmw`,
+    /// and is encoded into a buffer shared by the whole batch. An item that fails to
+    /// encode is reported as an error in its place.
+    pub fn rmw_batch<'i, K, V, I>(&self, items: I) -> Vec<BatchResult>
+    where
+        K: FasterKey + 'i,
+        V: FasterRmw + 'i,
+        I: IntoIterator<Item = (&'i K, &'i V)>,
+    {
+        let mut buffer = Vec::new();
+        self.run_batch(items, |(key, modification), monotonic_serial_number| {
+            let key_length = encode_pair::<K, V, C>(&mut buffer, key, modification)?;
+            let (encoded_key, encoded_modification) = buffer.split_at(key_length);
+            Ok(self.store.rmw_borrowed::<V>(
+                encoded_key,
+                encoded_modification,
+                monotonic_serial_number,
+            ))
+        })
+    }
+
+    fn run_batch<T, I, F>(&self, items: I, mut operation: F) -> Vec<BatchResult>
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(T, u64) -> BatchResult,
+    {
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                if index > 0 && index % BATCH_REFRESH_INTERVAL == 0 {
+                    self.refresh();
+                }
+                operation(item, self.next_serial_number())
+            })
+            .collect()
+    }
+
+    /// Upsert with a caller-supplied serial number, e.g. to align with an external log.
+    /// Subsequent operations continue numbering from `monotonic_serial_number`.
+    ///
+    /// Fails with `SerialNumberError`, without running the operation, if
+    /// `monotonic_serial_number` is not greater than `serial_number()`, as CPR
+    /// requires the serial numbers of a session to increase. The same holds for the
+    /// other `_with_serial` operations.
+    pub fn upsert_with_serial<K, V>(
+        &self,
+        key: &K,
+        value: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<u8, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        self.set_serial_number(monotonic_serial_number)?;
+        Ok(self.store.upsert(key, value, monotonic_serial_number))
+    }
+
+    pub fn read_with_serial<K, V>(
+        &self,
+        key: &K,
+        monotonic_serial_number: u64,
+    ) -> Result<(u8, Receiver<ReadResult<V>>), FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterValue,
+    {
+        self.set_serial_number(monotonic_serial_number)?;
+        Ok(self.store.read(key, monotonic_serial_number))
+    }
+
+    pub fn rmw_with_serial<K, V>(
+        &self,
+        key: &K,
+        value: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<u8, FasterError<'static>>
+    where
+        K: FasterKey,
+        V: FasterRmw,
+    {
+        self.set_serial_number(monotonic_serial_number)?;
+        Ok(self.store.rmw(key, value, monotonic_serial_number))
+    }
+
+    pub fn delete_with_serial<K>(
+        &self,
+        key: &K,
+        monotonic_serial_number: u64,
+    ) -> Result<u8, FasterError<'static>>
+    where
+        K: FasterKey,
+    {
+        self.set_serial_number(monotonic_serial_number)?;
+        Ok(self.store.delete(key, monotonic_serial_number))
+    }
+
+    pub fn complete_pending(&self, wait: bool) -> () {
+        self.store.complete_pending(wait);
+    }
+
+    // Completes what it can without waiting, returning true once nothing is pending
+    pub(crate) fn try_complete_pending(&self) -> bool {
+        self.store.try_complete_pending(false)
+    }
+
+    pub fn refresh(&self) -> () {
+        self.store.refresh();
+    }
+}
+
+// Encodes the key followed by the value, returning the length of the key
+fn encode_pair<K, V, C>(
+    buffer: &mut Vec<u8>,
+    key: &K,
+    value: &V,
+) -> Result<usize, FasterError<'static>>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    buffer.clear();
+    C::encode_into(key, buffer)?;
+    let key_length = buffer.len();
+    C::encode_into(value, buffer)?;
+    Ok(key_length)
+}
+
+// Make sure the session is never left registered with FASTER
+impl<'a, C: Codec> Drop for Session<'a, C> {
+    fn drop(&mut self) {
+        self.store.complete_pending(true);
+        self.store.stop_session();
+    }
+}
@@ -0,0 +1,115 @@
+/// Occupancy of the hash index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Number of buckets in the main hash table
+    pub table_size: u64,
+    /// Buckets of the main hash table holding at least one entry
+    pub used_buckets: u64,
+    /// Entries across all buckets, including overflow buckets
+    pub entries: u64,
+    /// Overflow buckets allocated for full buckets of the main hash table
+    pub overflow_buckets: u64,
+}
+
+impl IndexStats {
+    /// Fraction of the main hash table's buckets that are in use
+    pub fn occupancy(&self) -> f64 {
+        match self.table_size {
+            0 => 0.0,
+            table_size => self.used_buckets as f64 / table_size as f64,
+        }
+    }
+}
+
+/// A snapshot of the hybrid log's regions and of the hash index.
+///
+/// The log is divided by increasing addresses: records between `begin_address`
+/// and `head_address` are only on disk, records up to `read_only_address` are in
+/// memory but immutable, and records up to `tail_address` can be updated in place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogStats {
+    pub begin_address: u64,
+    pub head_address: u64,
+    pub safe_read_only_address: u64,
+    pub read_only_address: u64,
+    pub tail_address: u64,
+    pub mutable_bytes: u64,
+    pub read_only_bytes: u64,
+    pub on_disk_bytes: u64,
+    pub index: IndexStats,
+}
+
+impl LogStats {
+    pub(crate) fn new(stats: &ffi::faster_log_stats) -> LogStats {
+        LogStats {
+            begin_address: stats.begin_address,
+            head_address: stats.head_address,
+            safe_read_only_address: stats.safe_read_only_address,
+            read_only_address: stats.read_only_address,
+            tail_address: stats.tail_address,
+            mutable_bytes: stats.tail_address.saturating_sub(stats.read_only_address),
+            read_only_bytes: stats.read_only_address.saturating_sub(stats.head_address),
+            on_disk_bytes: stats.head_address.saturating_sub(stats.begin_address),
+            index: IndexStats {
+                table_size: stats.table_size,
+                used_buckets: stats.used_buckets,
+                entries: stats.entries,
+                overflow_buckets: stats.overflow_buckets,
+            },
+        }
+    }
+
+    /// Bytes of the log held in memory
+    pub fn in_memory_bytes(&self) -> u64 {
+        self.mutable_bytes + self.read_only_bytes
+    }
+}
+
+/// Number of entries a bucket of the hash index holds before it needs an overflow bucket
+pub const ENTRIES_PER_BUCKET: usize = 7;
+
+/// How the entries of the hash index are spread over its buckets
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexDistribution {
+    /// Number of buckets in the main hash table
+    pub table_size: u64,
+    pub entries: u64,
+    /// Keys that share both their bucket and their tag with another key, so reads
+    /// of those keys have to compare the keys in the log. Only the records still in
+    /// memory are counted.
+    pub tag_collisions: u64,
+    /// `histogram[i]` is the number of buckets holding `i` entries, counting their
+    /// overflow buckets. The last element counts every bucket with more entries.
+    pub histogram: Vec<u64>,
+}
+
+impl IndexDistribution {
+    pub(crate) unsafe fn of(faster_t: *mut ffi::faster_t) -> IndexDistribution {
+        let mut distribution: ffi::faster_index_distribution = std::mem::zeroed();
+        ffi::faster_get_index_distribution(faster_t, &mut distribution);
+        IndexDistribution {
+            table_size: distribution.table_size,
+            entries: distribution.entries,
+            tag_collisions: distribution.tag_collisions,
+            histogram: distribution.histogram.to_vec(),
+        }
+    }
+
+    /// Average number of entries per bucket, as a fraction of a bucket's capacity
+    pub fn load_factor(&self) -> f64 {
+        match self.table_size {
+            0 => 0.0,
+            table_size => self.entries as f64 / (table_size * ENTRIES_PER_BUCKET as u64) as f64,
+        }
+    }
+
+    /// Buckets that needed at least one overflow bucket
+    pub fn overflowing_buckets(&self) -> u64 {
+        self.histogram.iter().skip(ENTRIES_PER_BUCKET + 1).sum()
+    }
+
+    /// Whether the index should be grown to keep its load factor below `max_load_factor`
+    pub fn should_grow(&self, max_load_factor: f64) -> bool {
+        self.load_factor() > max_load_factor
+    }
+}
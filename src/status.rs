@@ -1,5 +1,8 @@
 // Represents Status values from FASTER
 
+use crate::FasterError;
+use std::convert::TryFrom;
+
 pub const OK: u8 = 0;
 pub const PENDING: u8 = 1;
 pub const NOT_FOUND: u8 = 2;
@@ -7,3 +10,91 @@ pub const OUT_OF_MEMORY: u8 = 3;
 pub const IO_ERROR: u8 = 4;
 pub const CORRUPTION: u8 = 5;
 pub const ABORTED: u8 = 6;
+
+#[must_use]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Pending,
+    NotFound,
+    OutOfMemory,
+    IoError,
+    Corruption,
+    Aborted,
+}
+
+impl Status {
+    /// Whether the status reports a failure rather than an outcome of the operation
+    pub fn is_error(self) -> bool {
+        match self {
+            Status::Ok | Status::Pending | Status::NotFound => false,
+            _ => true,
+        }
+    }
+
+    pub(crate) fn into_result(self) -> Result<Status, FasterError<'static>> {
+        if self.is_error() {
+            Err(FasterError::OperationError(self))
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl TryFrom<u8> for Status {
+    type Error = FasterError<'static>;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            OK => Ok(Status::Ok),
+            PENDING => Ok(Status::Pending),
+            NOT_FOUND => Ok(Status::NotFound),
+            OUT_OF_MEMORY => Ok(Status::OutOfMemory),
+            IO_ERROR => Ok(Status::IoError),
+            CORRUPTION => Ok(Status::Corruption),
+            ABORTED => Ok(Status::Aborted),
+            _ => Err(FasterError::InvalidStatus(value)),
+        }
+    }
+}
+
+impl From<Status> for u8 {
+    fn from(status: Status) -> u8 {
+        match status {
+            Status::Ok => OK,
+            Status::Pending => PENDING,
+            Status::NotFound => NOT_FOUND,
+            Status::OutOfMemory => OUT_OF_MEMORY,
+            Status::IoError => IO_ERROR,
+            Status::Corruption => CORRUPTION,
+            Status::Aborted => ABORTED,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn status_round_trips_through_ffi_value() {
+        for value in OK..=ABORTED {
+            let status = Status::try_from(value).unwrap();
+            assert_eq!(u8::from(status), value);
+        }
+    }
+
+    #[test]
+    fn unknown_ffi_value_errors() {
+        match Status::try_from(ABORTED + 1) {
+            Err(FasterError::InvalidStatus(value)) => assert_eq!(value, ABORTED + 1),
+            _ => assert!(false, "Should give InvalidStatus Error"),
+        }
+    }
+
+    #[test]
+    fn failures_become_errors() {
+        assert!(Status::NotFound.into_result().is_ok());
+        assert!(Status::IoError.into_result().is_err());
+    }
+}
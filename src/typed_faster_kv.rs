@@ -0,0 +1,316 @@
+use crate::status::{self, Status};
+use crate::util::{CheckPoint, Recover};
+use crate::{
+    Bincode, CheckpointCatalog, CheckpointRecord, Codec, FasterError, FasterKey, FasterKv,
+    FasterRmw, FasterValue, IndexDistribution, LogIter, LogStats, ReadResult, Session,
+};
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::mpsc::Receiver;
+
+/// A `FasterKv` whose Key and Value types are fixed when it is built.
+///
+/// The data operations only accept `K` and `V`, so every thread agrees on how
+/// records are encoded. Sessions started on it are `TypedSession`s, which are
+/// restricted to the same types. The untyped store is only reachable through
+/// `into_inner`.
+pub struct TypedFasterKv<K, V, C = Bincode>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    store: FasterKv<C>,
+    types: PhantomData<fn(K) -> V>,
+}
+
+impl<K, V, C> TypedFasterKv<K, V, C>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    pub(crate) fn new(store: FasterKv<C>) -> Self {
+        TypedFasterKv {
+            store,
+            types: PhantomData,
+        }
+    }
+
+    pub fn upsert(&self, key: &K, value: &V, monotonic_serial_number: u64) -> u8 {
+        self.store.upsert(key, value, monotonic_serial_number)
+    }
+
+    /// Reads the value stored for `key`, completing the operation if it went pending.
+    /// The value is `None` if the key does not exist.
+    pub fn read(&self, key: &K, monotonic_serial_number: u64) -> (u8, ReadResult<V>) {
+        let (status, recv) = self.store.read(key, monotonic_serial_number);
+        receive(status, recv, || self.store.complete_pending(true))
+    }
+
+    pub fn rmw_with<I, F>(&self, key: &K, input: &I, monotonic_serial_number: u64, update: F) -> u8
+    where
+        I: FasterValue,
+        F: Fn(Option<&V>, I) -> V + 'static,
+    {
+        self.store
+            .rmw_with(key, input, monotonic_serial_number, update)
+    }
+
+    pub fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<&V>,
+        new: &V,
+        monotonic_serial_number: u64,
+    ) -> Result<(bool, Option<V>), FasterError<'static>>
+    where
+        V: PartialEq + Clone + 'static,
+    {
+        self.store
+            .compare_and_swap(key, expected, new, monotonic_serial_number)
+    }
+
+    pub fn delete(&self, key: &K, monotonic_serial_number: u64) -> u8 {
+        self.store.delete(key, monotonic_serial_number)
+    }
+
+    /// Iterates over the live records of the store
+    pub fn iter(&self) -> LogIter<'_, K, V, C> {
+        self.store.iter()
+    }
+
+    pub fn start_session(&self) -> TypedSession<'_, K, V, C> {
+        TypedSession::new(self.store.start_session())
+    }
+
+    pub fn continue_session(&self, token: String) -> TypedSession<'_, K, V, C> {
+        TypedSession::new(self.store.continue_session(token))
+    }
+
+    pub fn complete_pending(&self, wait: bool) {
+        self.store.complete_pending(wait)
+    }
+
+    pub fn refresh(&self) {
+        self.store.refresh()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.store.size()
+    }
+
+    pub fn checkpoint(&self) -> Result<CheckPoint, FasterError<'_>> {
+        self.store.checkpoint()
+    }
+
+    pub fn checkpoint_index(&self) -> Result<CheckPoint, FasterError<'_>> {
+        self.store.checkpoint_index()
+    }
+
+    pub fn checkpoint_hybrid_log(&self) -> Result<CheckPoint, FasterError<'_>> {
+        self.store.checkpoint_hybrid_log()
+    }
+
+    pub fn checkpoint_history(&self) -> Vec<CheckpointRecord> {
+        self.store.checkpoint_history()
+    }
+
+    pub fn checkpoint_catalog(&self) -> Result<CheckpointCatalog, FasterError<'_>> {
+        self.store.checkpoint_catalog()
+    }
+
+    pub fn recover(
+        &self,
+        index_token: String,
+        hybrid_log_token: String,
+    ) -> Result<Recover, FasterError<'static>> {
+        self.store.recover(index_token, hybrid_log_token)
+    }
+
+    pub fn dump_distribution(&self) {
+        self.store.dump_distribution()
+    }
+
+    pub fn index_distribution(&self) -> IndexDistribution {
+        self.store.index_distribution()
+    }
+
+    pub fn log_begin_address(&self) -> u64 {
+        self.store.log_begin_address()
+    }
+
+    pub fn log_tail_address(&self) -> u64 {
+        self.store.log_tail_address()
+    }
+
+    pub fn log_stats(&self) -> LogStats {
+        self.store.log_stats()
+    }
+
+    pub fn compact(&self, until_address: u64) -> Result<(), FasterError<'static>> {
+        self.store.compact(until_address)
+    }
+
+    pub fn grow_index(&self) -> bool {
+        self.store.grow_index()
+    }
+
+    pub fn grow_index_if_needed(&self, max_load_factor: f64) -> bool {
+        self.store.grow_index_if_needed(max_load_factor)
+    }
+
+    // Warning: Calling this will remove the stored data
+    pub fn clean_storage(&self) -> Result<(), FasterError<'_>> {
+        self.store.clean_storage()
+    }
+
+    pub fn into_inner(self) -> FasterKv<C> {
+        self.store
+    }
+}
+
+impl<K, V, C> TypedFasterKv<K, V, C>
+where
+    K: FasterKey,
+    V: FasterRmw,
+    C: Codec,
+{
+    pub fn rmw(&self, key: &K, modification: &V, monotonic_serial_number: u64) -> u8 {
+        self.store.rmw(key, modification, monotonic_serial_number)
+    }
+}
+
+impl<K, V> Default for TypedFasterKv<K, V>
+where
+    K: FasterKey,
+    V: FasterValue,
+{
+    fn default() -> Self {
+        TypedFasterKv::new(FasterKv::default())
+    }
+}
+
+/// A `Session` of a `TypedFasterKv`, numbering its operations like `Session` does
+/// while only accepting the store's Key and Value types.
+pub struct TypedSession<'a, K, V, C = Bincode>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    session: Session<'a, C>,
+    types: PhantomData<fn(K) -> V>,
+}
+
+impl<'a, K, V, C> TypedSession<'a, K, V, C>
+where
+    K: FasterKey,
+    V: FasterValue,
+    C: Codec,
+{
+    fn new(session: Session<'a, C>) -> Self {
+        TypedSession {
+            session,
+            types: PhantomData,
+        }
+    }
+
+    /// The GUID identifying this session, used to continue it after recovery
+    pub fn id(&self) -> &str {
+        self.session.id()
+    }
+
+    /// The last serial number persisted by a checkpoint before this session was
+    /// continued, or 0 for a newly started session
+    pub fn persisted_serial_number(&self) -> u64 {
+        self.session.persisted_serial_number()
+    }
+
+    /// The most recent serial number used by this session
+    pub fn serial_number(&self) -> u64 {
+        self.session.serial_number()
+    }
+
+    pub fn upsert(&self, key: &K, value: &V) -> u8 {
+        self.session.upsert(key, value)
+    }
+
+    /// Reads the value stored for `key`, completing the operation if it went pending.
+    /// The value is `None` if the key does not exist.
+    pub fn read(&self, key: &K) -> (u8, ReadResult<V>) {
+        let (status, recv) = self.session.read(key);
+        receive(status, recv, || self.session.complete_pending(true))
+    }
+
+    pub fn rmw_with<I, F>(&self, key: &K, input: &I, update: F) -> u8
+    where
+        I: FasterValue,
+        F: Fn(Option<&V>, I) -> V + 'static,
+    {
+        self.session.rmw_with(key, input, update)
+    }
+
+    pub fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<&V>,
+        new: &V,
+    ) -> Result<(bool, Option<V>), FasterError<'static>>
+    where
+        V: PartialEq + Clone + 'static,
+    {
+        self.session.compare_and_swap(key, expected, new)
+    }
+
+    pub fn delete(&self, key: &K) -> u8 {
+        self.session.delete(key)
+    }
+
+    pub fn complete_pending(&self, wait: bool) {
+        self.session.complete_pending(wait)
+    }
+
+    pub fn refresh(&self) {
+        self.session.refresh()
+    }
+}
+
+impl<'a, K, V, C> TypedSession<'a, K, V, C>
+where
+    K: FasterKey,
+    V: FasterRmw,
+    C: Codec,
+{
+    pub fn rmw(&self, key: &K, modification: &V) -> u8 {
+        self.session.rmw(key, modification)
+    }
+}
+
+// Waits for the result of a read, completing it first if it went pending
+fn receive<V, F>(
+    status: u8,
+    recv: Receiver<ReadResult<V>>,
+    complete_pending: F,
+) -> (u8, ReadResult<V>)
+where
+    F: FnOnce(),
+{
+    match status {
+        status::OK | status::NOT_FOUND => {}
+        status::PENDING => complete_pending(),
+        // Nothing is sent on the receiver when the read fails outright
+        _ => {
+            let err = match Status::try_from(status) {
+                Ok(status) => FasterError::OperationError(status),
+                Err(err) => err,
+            };
+            return (status, Err(err));
+        }
+    }
+    let result = recv
+        .recv()
+        .unwrap_or_else(|_| Err(FasterError::OperationError(Status::Aborted)));
+    (status, result)
+}
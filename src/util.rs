@@ -1,6 +1,14 @@
 extern crate libc;
 extern crate libfaster_sys as ffi;
 
+use crate::instrumentation::{self, Timer};
+use crate::FasterError;
+
+use std::ffi::CStr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
 pub struct CheckPoint {
     pub checked: bool,
     pub token: String,
@@ -10,4 +18,104 @@ pub struct Recover {
     pub status: u8,
     pub version: u32,
     pub session_ids: Vec<String>,
+    /// The GUID of each recovered session paired with the last serial number
+    /// persisted for it, as read from the hybrid log checkpoint. Empty if the
+    /// checkpoint's metadata could not be read.
+    pub sessions: Vec<(String, u64)>,
+}
+
+pub(crate) type CheckpointFn =
+    unsafe extern "C" fn(*mut ffi::faster_t) -> *mut ffi::faster_checkpoint_result;
+
+pub(crate) unsafe fn take_checkpoint(
+    faster_t: *mut ffi::faster_t,
+    checkpoint_fn: CheckpointFn,
+    kind: &'static str,
+) -> Result<CheckPoint, FasterError<'static>> {
+    let timer = Timer::start();
+    let checkpoint = checkpoint_result(checkpoint_fn(faster_t));
+    instrumentation::checkpoint(kind, &checkpoint, timer);
+    checkpoint
+}
+
+unsafe fn checkpoint_result(
+    result: *mut ffi::faster_checkpoint_result,
+) -> Result<CheckPoint, FasterError<'static>> {
+    match result.is_null() {
+        true => Err(FasterError::CheckpointError),
+        false => {
+            let token_str = CStr::from_ptr((*result).token).to_str().unwrap().to_owned();
+
+            let checkpoint = CheckPoint {
+                checked: (*result).checked,
+                token: token_str,
+            };
+            ffi::faster_checkpoint_result_free(result);
+            Ok(checkpoint)
+        }
+    }
+}
+
+pub(crate) struct FasterPtr(pub *mut ffi::faster_t);
+
+// FASTER's maintenance operations may be started from any thread
+unsafe impl Send for FasterPtr {}
+
+struct Stop {
+    stopped: Mutex<bool>,
+    wake: Condvar,
+}
+
+/// Thread calling `tick` at a fixed interval until it is stopped
+pub(crate) struct BackgroundThread {
+    stop: Arc<Stop>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BackgroundThread {
+    pub(crate) fn start<F>(name: &str, interval: Duration, mut tick: F) -> BackgroundThread
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let stop = Arc::new(Stop {
+            stopped: Mutex::new(false),
+            wake: Condvar::new(),
+        });
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::Builder::new()
+            .name(String::from(name))
+            .spawn(move || loop {
+                {
+                    let stopped = thread_stop.stopped.lock().unwrap();
+                    if *stopped {
+                        return;
+                    }
+                    let (stopped, _) = thread_stop.wake.wait_timeout(stopped, interval).unwrap();
+                    if *stopped {
+                        return;
+                    }
+                }
+                tick();
+            })
+            .unwrap();
+        BackgroundThread {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Stops the thread, which must happen before the store is destroyed
+    pub(crate) fn stop(&mut self) {
+        *self.stop.stopped.lock().unwrap() = true;
+        self.stop.wake.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for BackgroundThread {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
@@ -0,0 +1,100 @@
+extern crate faster_rs;
+extern crate futures;
+extern crate tempfile;
+
+use faster_rs::{status, FasterKv, FasterKvBuilder, ReadResult};
+use futures::executor::block_on;
+use futures::future::join_all;
+use std::sync::mpsc::Receiver;
+use tempfile::TempDir;
+
+const LOG_SIZE: u64 = 64 * 1024 * 1024;
+// Enough 1 KiB values to push the first records out of the in-memory log
+const DISK_RECORDS: u64 = 2 * LOG_SIZE / 1024;
+
+#[test]
+fn async_read_inserted_value() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+
+    block_on(async {
+        session.upsert_async(&key, &value).await.unwrap();
+        let read: Option<u64> = session.read_async(&key).await.unwrap();
+        assert_eq!(read, Some(value));
+    });
+}
+
+#[test]
+fn async_read_missing_value() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+
+    let read: Option<u64> = block_on(session.read_async(&key)).unwrap();
+    assert_eq!(read, None);
+}
+
+#[test]
+fn async_rmw_changes_value() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+    let modification: u64 = 100;
+
+    block_on(async {
+        session.upsert_async(&key, &value).await.unwrap();
+        session.rmw_async(&key, &modification).await.unwrap();
+        let read: Option<u64> = session.read_async(&key).await.unwrap();
+        assert_eq!(read, Some(value + modification));
+    });
+}
+
+#[test]
+fn async_rmw_fetch() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+
+    block_on(async {
+        session.upsert_async(&key, &(10 as u64)).await.unwrap();
+        let fetched = session.rmw_fetch_async(&key, &(5 as u64)).await.unwrap();
+        assert_eq!(fetched.before, Some(10));
+        assert_eq!(fetched.after, 15);
+    });
+}
+
+#[test]
+fn async_read_from_disk() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = FasterKvBuilder::new(1 << 15, LOG_SIZE)
+        .with_disk(&dir_path)
+        .build()
+        .unwrap();
+    let session = store.start_session();
+    for key in 0..DISK_RECORDS {
+        session.upsert(&key, &vec![(key % 256) as u8; 1024]);
+    }
+    session.complete_pending(true);
+
+    // Make sure the first records really are read from disk
+    let (res, recv): (u8, Receiver<ReadResult<Vec<u8>>>) = session.read(&0u64);
+    assert_eq!(res, status::PENDING);
+    session.complete_pending(true);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(vec![0; 1024]));
+
+    let keys: Vec<u64> = (1..100).collect();
+    let values: Vec<Option<Vec<u8>>> = block_on(join_all(
+        keys.iter()
+            .map(|key| session.read_async::<u64, Vec<u8>>(key)),
+    ))
+    .into_iter()
+    .map(|value| value.unwrap())
+    .collect();
+    for (key, value) in keys.iter().zip(values) {
+        assert_eq!(value, Some(vec![*key as u8; 1024]));
+    }
+}
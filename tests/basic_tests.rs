@@ -1,6 +1,6 @@
 extern crate faster_rs;
 
-use faster_rs::{status, FasterKv};
+use faster_rs::{status, FasterError, FasterKv, ReadResult, Status};
 use std::collections::HashSet;
 use std::sync::mpsc::Receiver;
 
@@ -28,19 +28,19 @@ fn faster_read_inserted_value() {
     let upsert = store.upsert(&key, &value, 1);
     assert!((upsert == status::OK || upsert == status::PENDING) == true);
 
-    let (res, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(res == status::OK);
-    assert!(recv.recv().unwrap() == value);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
 }
 
 #[test]
-fn faster_read_missing_value_recv_error() {
+fn faster_read_missing_value_recv_none() {
     let store = FasterKv::default();
     let key: u64 = 1;
 
-    let (res, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(res == status::NOT_FOUND);
-    assert!(recv.recv().is_err());
+    assert_eq!(recv.recv().unwrap().unwrap(), None);
 }
 
 #[test]
@@ -53,16 +53,16 @@ fn faster_rmw_changes_values() {
     let upsert = store.upsert(&key, &value, 1);
     assert!((upsert == status::OK || upsert == status::PENDING) == true);
 
-    let (res, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(res == status::OK);
-    assert!(recv.recv().unwrap() == value);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
 
     let rmw = store.rmw(&key, &modification, 1);
     assert!((rmw == status::OK || rmw == status::PENDING) == true);
 
-    let (res, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(res == status::OK);
-    assert!(recv.recv().unwrap() == value + modification);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value + modification));
 }
 
 #[test]
@@ -74,9 +74,9 @@ fn faster_rmw_without_upsert() {
     let rmw = store.rmw(&key, &modification, 1);
     assert!((rmw == status::OK || rmw == status::PENDING) == true);
 
-    let (res, recv): (u8, Receiver<u64>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
     assert!(res == status::OK);
-    assert!(recv.recv().unwrap() == modification);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(modification));
 }
 
 #[test]
@@ -89,16 +89,16 @@ fn faster_rmw_string() {
     let upsert = store.upsert(&key, &value, 1);
     assert!(upsert == status::OK || upsert == status::PENDING);
 
-    let (res, recv): (u8, Receiver<String>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), value);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
 
     let rmw = store.rmw(&key, &modification, 1);
     assert!(rmw == status::OK || rmw == status::PENDING);
 
-    let (res, recv): (u8, Receiver<String>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), String::from("Hello, World!"));
+    assert_eq!(recv.recv().unwrap().unwrap().unwrap(), "Hello, World!");
 }
 
 #[test]
@@ -112,23 +112,26 @@ fn faster_rmw_vec() {
     let upsert = store.upsert(&key, &value, 1);
     assert!(upsert == status::OK || upsert == status::PENDING);
 
-    let (res, recv): (u8, Receiver<Vec<i32>>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<Vec<i32>>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), value);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
 
     let rmw = store.rmw(&key, &modification, 1);
     assert!(rmw == status::OK || rmw == status::PENDING);
 
-    let (res, recv): (u8, Receiver<Vec<i32>>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<Vec<i32>>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(vec![0, 1, 2, 3, 4, 5]));
 
     let rmw = store.rmw(&key, &modification2, 1);
     assert!(rmw == status::OK || rmw == status::PENDING);
 
-    let (res, recv): (u8, Receiver<Vec<i32>>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<Vec<i32>>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    assert_eq!(
+        recv.recv().unwrap().unwrap(),
+        Some(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+    );
 }
 
 #[test]
@@ -141,9 +144,9 @@ fn faster_rmw_grow_string() {
         store.rmw(&key, &letter, 1);
     }
 
-    let (res, recv): (u8, Receiver<String>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    assert_eq!(recv.recv().unwrap(), final_string);
+    assert_eq!(recv.recv().unwrap().unwrap().unwrap(), final_string);
 }
 
 #[test]
@@ -158,9 +161,9 @@ fn faster_rmw_hashset() {
         let b: HashSet<i32> = [4, 2, 3, 4, 5].iter().cloned().collect();
         store.rmw(&key, &b, 1);
     }
-    let (res, recv): (u8, Receiver<HashSet<i32>>) = store.read(&key, 1);
+    let (res, recv): (u8, Receiver<ReadResult<HashSet<i32>>>) = store.read(&key, 1);
     assert_eq!(res, status::OK);
-    let hash_set = recv.recv().unwrap();
+    let hash_set = recv.recv().unwrap().unwrap().unwrap();
     assert_eq!(hash_set.len(), 5);
     assert!(hash_set.contains(&1));
     assert!(hash_set.contains(&2));
@@ -168,3 +171,113 @@ fn faster_rmw_hashset() {
     assert!(hash_set.contains(&4));
     assert!(hash_set.contains(&5));
 }
+
+#[test]
+fn faster_delete_removes_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+
+    let delete = store.delete(&key, 1);
+    assert!(delete == status::OK || delete == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(recv.recv().unwrap().unwrap(), None);
+}
+
+#[test]
+fn faster_delete_missing_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    let delete = store.delete(&key, 1);
+    assert_eq!(delete, status::NOT_FOUND);
+}
+
+#[test]
+fn faster_rmw_after_delete() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+    let modification: u64 = 100;
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let delete = store.delete(&key, 1);
+    assert!(delete == status::OK || delete == status::PENDING);
+
+    let rmw = store.rmw(&key, &modification, 1);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(modification));
+}
+
+#[test]
+fn faster_try_operations() -> Result<(), FasterError<'static>> {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+    let modification: u64 = 100;
+
+    let upsert = store.try_upsert(&key, &value, 1)?;
+    assert!(upsert == Status::Ok || upsert == Status::Pending);
+
+    let rmw = store.try_rmw(&key, &modification, 1)?;
+    assert!(rmw == Status::Ok || rmw == Status::Pending);
+
+    let (read, recv): (Status, Receiver<ReadResult<u64>>) = store.try_read(&key, 1)?;
+    assert_eq!(read, Status::Ok);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value + modification));
+
+    let delete = store.try_delete(&key, 1)?;
+    assert!(delete == Status::Ok || delete == Status::Pending);
+
+    let (read, _): (Status, Receiver<ReadResult<u64>>) = store.try_read(&key, 1)?;
+    assert_eq!(read, Status::NotFound);
+    Ok(())
+}
+
+#[test]
+fn faster_read_wrong_type_errors() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u8 = 42;
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    match recv.recv().unwrap() {
+        Err(FasterError::DeserializationError(_)) => assert!(true),
+        _ => assert!(false, "Should give DeserializationError"),
+    }
+}
+
+#[test]
+fn faster_rmw_wrong_type_keeps_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u8 = 42;
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let rmw = store.rmw(&key, &(100 as u64), 1);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u8>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+}
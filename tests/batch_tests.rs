@@ -0,0 +1,123 @@
+extern crate faster_rs;
+
+use faster_rs::{status, BatchResult, FasterError, FasterKv, ReadResult};
+use futures::executor::block_on;
+use serde::ser::{Error, Serializer};
+use serde::Serialize;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+
+#[test]
+fn upsert_batch_read_batch() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let items: Vec<(u64, String)> = (0..1000).map(|i| (i, i.to_string())).collect();
+
+    let statuses = session.upsert_batch(items.iter().map(|(key, value)| (key, value)));
+    assert_eq!(statuses.len(), items.len());
+    assert!(statuses.iter().all(|status| match status {
+        Ok(status) => *status == status::OK || *status == status::PENDING,
+        Err(_) => false,
+    }));
+    assert_eq!(session.serial_number(), 1000);
+
+    let keys: Vec<u64> = items.iter().map(|(key, _)| *key).collect();
+    let (statuses, recv): (Vec<BatchResult>, Receiver<(usize, ReadResult<String>)>) =
+        session.read_batch(&keys);
+    session.complete_pending(true);
+    assert_eq!(statuses.len(), keys.len());
+
+    let values: HashMap<usize, String> = recv
+        .try_iter()
+        .map(|(index, result)| (index, result.unwrap().unwrap()))
+        .collect();
+    assert_eq!(values.len(), keys.len());
+    for (index, (_, value)) in items.iter().enumerate() {
+        assert_eq!(&values[&index], value);
+    }
+}
+
+#[test]
+fn read_batch_missing_keys() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+
+    session.upsert(&(2 as u64), &(20 as u64));
+    let keys: Vec<u64> = vec![1, 2, 3];
+    let results = block_on(session.read_batch_async::<u64, u64, _>(&keys));
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap(), &None);
+    assert_eq!(results[1].as_ref().unwrap(), &Some(20));
+    assert_eq!(results[2].as_ref().unwrap(), &None);
+}
+
+#[test]
+fn rmw_batch_updates_repeated_keys() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let items: Vec<(u64, u64)> = (0..300).map(|i| (i % 3, i)).collect();
+
+    let statuses = session.rmw_batch(items.iter().map(|(key, modification)| (key, modification)));
+    assert_eq!(statuses.len(), items.len());
+    assert!(statuses.iter().all(|status| status.is_ok()));
+    session.complete_pending(true);
+
+    let keys: Vec<u64> = vec![0, 1, 2];
+    let results = block_on(session.read_batch_async::<u64, u64, _>(&keys));
+    for (key, result) in keys.iter().zip(results) {
+        let expected: u64 = (0..300).filter(|i| i % 3 == *key).sum();
+        assert_eq!(result.unwrap(), Some(expected));
+    }
+}
+
+// A value that refuses to be encoded when odd
+#[derive(Deserialize, Debug, PartialEq)]
+struct EvenOnly(u64);
+
+impl Serialize for EvenOnly {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0 % 2 == 1 {
+            return Err(S::Error::custom("odd values cannot be encoded"));
+        }
+        serializer.serialize_newtype_struct("EvenOnly", &self.0)
+    }
+}
+
+#[test]
+fn batch_reports_encode_failures_per_item() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let items: Vec<(u64, EvenOnly)> = (0..4).map(|i| (i, EvenOnly(i))).collect();
+
+    let statuses = session.upsert_batch(items.iter().map(|(key, value)| (key, value)));
+    assert_eq!(statuses.len(), items.len());
+    for (key, status) in statuses.iter().enumerate() {
+        match status {
+            Ok(status) => {
+                assert_eq!(key % 2, 0);
+                assert!(*status == status::OK || *status == status::PENDING);
+            }
+            Err(FasterError::SerializationError(_)) => assert_eq!(key % 2, 1),
+            Err(err) => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    let keys: Vec<u64> = (0..4).collect();
+    let results = block_on(session.read_batch_async::<u64, EvenOnly, _>(&keys));
+    assert_eq!(results[0].as_ref().unwrap(), &Some(EvenOnly(0)));
+    assert_eq!(results[1].as_ref().unwrap(), &None);
+    assert_eq!(results[2].as_ref().unwrap(), &Some(EvenOnly(2)));
+    assert_eq!(results[3].as_ref().unwrap(), &None);
+}
+
+#[test]
+fn empty_batch() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let keys: Vec<u64> = Vec::new();
+
+    let results = block_on(session.read_batch_async::<u64, u64, _>(&keys));
+    assert!(results.is_empty());
+    assert_eq!(session.serial_number(), 0);
+}
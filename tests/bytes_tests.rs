@@ -0,0 +1,93 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterKv, ReadResult};
+use std::sync::mpsc::{channel, Receiver};
+
+fn read_bytes(store: &FasterKv, key: &[u8]) -> (u8, Option<Vec<u8>>) {
+    let (sender, receiver) = channel();
+    let res = store.read_bytes(key, 1, move |result| {
+        sender
+            .send(result.unwrap().map(|value| value.to_vec()))
+            .unwrap();
+    });
+    store.complete_pending(true);
+    (res, receiver.recv().unwrap())
+}
+
+#[test]
+fn upsert_read_bytes() {
+    let store = FasterKv::default();
+    let key = b"key";
+    let value = b"value";
+
+    let upsert = store.upsert_bytes(key, value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, read) = read_bytes(&store, key);
+    assert!(res == status::OK || res == status::PENDING);
+    assert_eq!(read.unwrap(), value);
+}
+
+#[test]
+fn read_bytes_missing_key() {
+    let store = FasterKv::default();
+    let (res, read) = read_bytes(&store, b"missing");
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(read, None);
+}
+
+#[test]
+fn rmw_bytes_merges_values() {
+    let store = FasterKv::default();
+    let key = b"key";
+    let append = |current: &[u8], modification: &[u8]| [current, modification].concat();
+
+    // The modification becomes the initial value of a missing key
+    let rmw = store.rmw_bytes(key, b"Hello, ", 1, append);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    let rmw = store.rmw_bytes(key, b"World!", 1, append);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (_, read) = read_bytes(&store, key);
+    assert_eq!(read.unwrap(), b"Hello, World!");
+}
+
+#[test]
+fn session_bytes_operations() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key = 1u64.to_le_bytes();
+
+    session.upsert_bytes(&key, &10u64.to_le_bytes());
+    session.rmw_bytes(&key, &5u64.to_le_bytes(), |current, modification| {
+        let mut sum = [0; 8];
+        sum.copy_from_slice(current);
+        let mut add = [0; 8];
+        add.copy_from_slice(modification);
+        (u64::from_le_bytes(sum) + u64::from_le_bytes(add))
+            .to_le_bytes()
+            .to_vec()
+    });
+
+    let (sender, receiver) = channel();
+    session.read_bytes(&key, move |result| {
+        sender.send(result.unwrap().unwrap().to_vec()).unwrap();
+    });
+    session.complete_pending(true);
+    assert_eq!(receiver.recv().unwrap(), 15u64.to_le_bytes());
+    assert_eq!(session.serial_number(), 3);
+}
+
+#[test]
+fn bytes_written_with_codec_encoding_can_be_read_typed() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value = String::from("value");
+    let encoded_key = bincode::serialize(&key).unwrap();
+    let encoded_value = bincode::serialize(&value).unwrap();
+
+    store.upsert_bytes(&encoded_key, &encoded_value, 1);
+    let (res, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+}
@@ -0,0 +1,115 @@
+extern crate faster_rs;
+extern crate tempfile;
+
+use faster_rs::{CheckpointKind, FasterError, FasterKv, FasterKvBuilder, RetentionPolicy, Session};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn build_store(dir_path: &str) -> FasterKv {
+    let mut builder = FasterKvBuilder::new(1 << 14, 1073741824);
+    builder.with_disk(dir_path);
+    builder.build().unwrap()
+}
+
+// Checkpoints complete as sessions make progress
+fn wait_for_checkpoint(store: &FasterKv, session: &Session, token: &str, kind: CheckpointKind) {
+    let catalog = store.checkpoint_catalog().unwrap();
+    for _ in 0..1000 {
+        session.complete_pending(true);
+        session.refresh();
+        let checkpoints = catalog.list_kind(kind).unwrap();
+        if checkpoints
+            .iter()
+            .any(|checkpoint| checkpoint.token == token)
+        {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("Checkpoint {} did not complete", token);
+}
+
+#[test]
+fn catalog_lists_checkpoints() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = build_store(&dir_path);
+    let session = store.start_session();
+
+    for key in 0..1000 {
+        session.upsert(&(key as u64), &(100 as u64));
+    }
+    let full = store.checkpoint().unwrap().token;
+    wait_for_checkpoint(&store, &session, &full, CheckpointKind::Index);
+    wait_for_checkpoint(&store, &session, &full, CheckpointKind::HybridLog);
+    let log = store.checkpoint_hybrid_log().unwrap().token;
+    wait_for_checkpoint(&store, &session, &log, CheckpointKind::HybridLog);
+
+    let catalog = store.checkpoint_catalog().unwrap();
+    let checkpoints = catalog.list().unwrap();
+    assert_eq!(checkpoints.len(), 3);
+    assert!(checkpoints.iter().all(|checkpoint| checkpoint.size > 0));
+
+    let indexes = catalog.list_kind(CheckpointKind::Index).unwrap();
+    assert_eq!(indexes.len(), 1);
+    assert_eq!(indexes[0].token, full);
+
+    let (index, hybrid_log) = catalog.latest_recoverable().unwrap().unwrap();
+    assert_eq!(index.token, full);
+    assert_eq!(hybrid_log.token, log);
+}
+
+#[test]
+fn catalog_retention_keeps_latest_recoverable() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = build_store(&dir_path);
+    let session = store.start_session();
+
+    let mut tokens = Vec::new();
+    for i in 0..3 {
+        session.upsert(&(i as u64), &(100 as u64));
+        let token = store.checkpoint().unwrap().token;
+        wait_for_checkpoint(&store, &session, &token, CheckpointKind::Index);
+        wait_for_checkpoint(&store, &session, &token, CheckpointKind::HybridLog);
+        tokens.push(token);
+    }
+
+    let catalog = store.checkpoint_catalog().unwrap();
+    let deleted = catalog
+        .apply_retention(RetentionPolicy::KeepLatest(1))
+        .unwrap();
+    assert_eq!(deleted.len(), 4);
+
+    let remaining = catalog.list().unwrap();
+    assert_eq!(remaining.len(), 2);
+    assert!(remaining
+        .iter()
+        .all(|checkpoint| checkpoint.token == tokens[2]));
+
+    let deleted = catalog
+        .apply_retention(RetentionPolicy::MaxAge(Duration::from_secs(0)))
+        .unwrap();
+    assert!(deleted.is_empty());
+}
+
+#[test]
+fn catalog_empty_directory() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = build_store(&dir_path);
+
+    let catalog = store.checkpoint_catalog().unwrap();
+    assert!(catalog.list().unwrap().is_empty());
+    assert!(catalog.latest_recoverable().unwrap().is_none());
+}
+
+#[test]
+fn in_memory_catalog_errors() {
+    let store = FasterKv::default();
+    match store.checkpoint_catalog() {
+        Err(FasterError::InvalidType) => assert!(true),
+        _ => assert!(false, "Should give InvalidType Error"),
+    }
+}
@@ -1,7 +1,12 @@
 extern crate faster_rs;
 extern crate tempfile;
 
-use faster_rs::{FasterError, FasterKv, FasterKvBuilder};
+use faster_rs::{
+    status, CheckpointMode, CheckpointSchedule, FasterError, FasterKv, FasterKvBuilder, ReadResult,
+};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 
 #[test]
@@ -64,6 +69,148 @@ fn single_checkpoint_hybrid_log() {
     assert_eq!(checkpoint.token.len(), 37 - 1); // -1 \0
 }
 
+#[test]
+fn delete_survives_recovery() {
+    let table_size: u64 = 1 << 14;
+    let log_size: u64 = 1073741824;
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let value: u64 = 100;
+
+    let token = {
+        let mut builder = FasterKvBuilder::new(table_size, log_size);
+        builder.with_disk(&dir_path);
+        let store = builder.build().unwrap();
+        let session = store.start_session();
+
+        for key in 0..1000 {
+            session.upsert(&(key as u64), &value);
+        }
+        for key in 0..500 {
+            session.delete(&(key as u64));
+        }
+
+        let checkpoint = store.checkpoint().unwrap();
+        assert_eq!(checkpoint.checked, true);
+        checkpoint.token
+    };
+
+    let mut builder = FasterKvBuilder::new(table_size, log_size);
+    builder.with_disk(&dir_path);
+    let store = builder.build().unwrap();
+    let recover = store.recover(token.clone(), token).unwrap();
+    assert_eq!(recover.session_ids.len(), 1);
+    let session = store.continue_session(recover.session_ids[0].clone());
+    assert_eq!(
+        recover.sessions,
+        vec![(session.id().to_owned(), session.persisted_serial_number())]
+    );
+    assert_eq!(session.persisted_serial_number(), 1500);
+
+    for key in 0..1000 {
+        let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(key as u64));
+        session.complete_pending(true);
+        if key < 500 {
+            assert!(res == status::NOT_FOUND || res == status::PENDING);
+            assert_eq!(recv.recv().unwrap().unwrap(), None);
+        } else {
+            assert!(res == status::OK || res == status::PENDING);
+            assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+        }
+    }
+}
+
+#[test]
+fn recover_old_index_with_newer_hybrid_log() {
+    let table_size: u64 = 1 << 14;
+    let log_size: u64 = 1073741824;
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let value: u64 = 100;
+
+    let (index_token, hybrid_log_token) = {
+        let mut builder = FasterKvBuilder::new(table_size, log_size);
+        builder.with_disk(&dir_path);
+        let store = builder.build().unwrap();
+        let session = store.start_session();
+
+        for key in 0..1000 {
+            session.upsert(&(key as u64), &value);
+        }
+        let index_checkpoint = store.checkpoint_index().unwrap();
+        assert_eq!(index_checkpoint.checked, true);
+        session.complete_pending(true);
+
+        for key in 1000..2000 {
+            session.upsert(&(key as u64), &value);
+        }
+        let log_checkpoint = store.checkpoint_hybrid_log().unwrap();
+        assert_eq!(log_checkpoint.checked, true);
+        assert_ne!(index_checkpoint.token, log_checkpoint.token);
+        (index_checkpoint.token, log_checkpoint.token)
+    };
+
+    let mut builder = FasterKvBuilder::new(table_size, log_size);
+    builder.with_disk(&dir_path);
+    let store = builder.build().unwrap();
+    let recover = store.recover(index_token, hybrid_log_token).unwrap();
+    assert_eq!(recover.session_ids.len(), 1);
+    let session = store.continue_session(recover.session_ids[0].clone());
+
+    for key in 0..2000 {
+        let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(key as u64));
+        assert!(res == status::OK || res == status::PENDING);
+        session.complete_pending(true);
+        assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+    }
+}
+
+#[test]
+fn scheduled_checkpoints_become_durable() {
+    let table_size: u64 = 1 << 14;
+    let log_size: u64 = 1073741824;
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let (sender, receiver) = channel();
+    let sender = Mutex::new(sender);
+    let schedule = CheckpointSchedule::every(Duration::from_millis(200))
+        .with_mode(CheckpointMode::HybridLog)
+        .on_durable(move |record| {
+            let _ = sender.lock().unwrap().send(record.token.clone());
+        });
+    let mut builder = FasterKvBuilder::new(table_size, log_size);
+    builder
+        .with_disk(&dir_path)
+        .with_checkpoint_schedule(schedule);
+    let store = builder.build().unwrap();
+    let session = store.start_session();
+
+    // Sessions must keep making progress for checkpoints to complete
+    let start = Instant::now();
+    let mut key: u64 = 0;
+    let token = loop {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "No durable checkpoint"
+        );
+        session.upsert(&key, &key);
+        session.refresh();
+        session.complete_pending(false);
+        key += 1;
+        if let Ok(token) = receiver.try_recv() {
+            break token.unwrap();
+        }
+    };
+
+    let history = store.checkpoint_history();
+    let record = history
+        .iter()
+        .find(|record| record.token.as_ref() == Some(&token))
+        .unwrap();
+    assert_eq!(record.mode, CheckpointMode::HybridLog);
+    assert!(record.durable.is_some());
+}
+
 #[test]
 fn concurrent_checkpoints() {
     //TODO
@@ -119,3 +266,107 @@ fn in_memory_checkpoint_hybrid_log_errors() {
         _ => assert!(false, "Should give InvalidType Error"),
     }
 }
+
+#[test]
+fn open_or_recover_without_checkpoints() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let mut builder = FasterKvBuilder::new(1 << 14, 1073741824);
+    builder.with_disk(&dir_path);
+    let (store, recover) = builder.open_or_recover().unwrap();
+    assert!(recover.is_none());
+    let session = store.start_session();
+    assert_eq!(session.upsert(&(1 as u64), &(1 as u64)), status::OK);
+}
+
+#[test]
+fn open_or_recover_latest_checkpoint() {
+    let table_size: u64 = 1 << 14;
+    let log_size: u64 = 1073741824;
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let value: u64 = 100;
+
+    {
+        let mut builder = FasterKvBuilder::new(table_size, log_size);
+        builder.with_disk(&dir_path);
+        let store = builder.build().unwrap();
+        let session = store.start_session();
+        for key in 0..1000 {
+            session.upsert(&(key as u64), &value);
+        }
+        assert!(store.checkpoint().unwrap().checked);
+        session.complete_pending(true);
+        for key in 1000..2000 {
+            session.upsert(&(key as u64), &value);
+        }
+        assert!(store.checkpoint().unwrap().checked);
+    }
+
+    let mut builder = FasterKvBuilder::new(table_size, log_size);
+    builder.with_disk(&dir_path);
+    let (store, recover) = builder.open_or_recover().unwrap();
+    let recover = recover.unwrap();
+    assert_eq!(recover.session_ids.len(), 1);
+    assert_eq!(
+        recover.sessions,
+        vec![(recover.session_ids[0].clone(), 2000)]
+    );
+
+    let session = store.start_session();
+    for key in 0..2000 {
+        let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(key as u64));
+        assert!(res == status::OK || res == status::PENDING);
+        session.complete_pending(true);
+        assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+    }
+}
+
+#[test]
+fn open_or_recover_pinned_checkpoint() {
+    let table_size: u64 = 1 << 14;
+    let log_size: u64 = 1073741824;
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let value: u64 = 100;
+
+    let pinned_token = {
+        let mut builder = FasterKvBuilder::new(table_size, log_size);
+        builder.with_disk(&dir_path);
+        let store = builder.build().unwrap();
+        let session = store.start_session();
+        for key in 0..1000 {
+            session.upsert(&(key as u64), &value);
+        }
+        let pinned = store.checkpoint().unwrap();
+        assert!(pinned.checked);
+        session.complete_pending(true);
+        for key in 1000..2000 {
+            session.upsert(&(key as u64), &value);
+        }
+        assert!(store.checkpoint().unwrap().checked);
+        pinned.token
+    };
+
+    let mut builder = FasterKvBuilder::new(table_size, log_size);
+    builder
+        .with_disk(&dir_path)
+        .with_recovery_tokens(&pinned_token, &pinned_token);
+    let (store, recover) = builder.open_or_recover().unwrap();
+    assert!(recover.is_some());
+
+    let session = store.start_session();
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(1500 as u64));
+    assert!(res == status::NOT_FOUND || res == status::PENDING);
+    session.complete_pending(true);
+    assert_eq!(recv.recv().unwrap().unwrap(), None);
+}
+
+#[test]
+fn open_or_recover_requires_disk() {
+    let builder = FasterKvBuilder::new(1 << 14, 1073741824);
+    match builder.open_or_recover() {
+        Err(FasterError::BuilderError(_)) => assert!(true),
+        _ => assert!(false, "Should give BuilderError"),
+    }
+}
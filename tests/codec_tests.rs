@@ -0,0 +1,116 @@
+extern crate faster_rs;
+
+use faster_rs::{status, Codec, FasterError, FasterKv, FasterKvBuilder, ReadResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::mpsc::Receiver;
+
+// bincode with a version byte in front of every key and value
+struct Versioned;
+
+const VERSION: u8 = 7;
+
+impl Codec for Versioned {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FasterError<'static>> {
+        let mut encoded = vec![VERSION];
+        bincode::serialize_into(&mut encoded, value)
+            .map_err(|err| FasterError::SerializationError(err.to_string()))?;
+        Ok(encoded)
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, FasterError<'static>> {
+        match bytes.split_first() {
+            Some((&VERSION, rest)) => bincode::deserialize(rest)
+                .map_err(|err| FasterError::DeserializationError(err.to_string())),
+            _ => Err(FasterError::DeserializationError(String::from(
+                "unknown version",
+            ))),
+        }
+    }
+}
+
+fn new_store() -> FasterKv<Versioned> {
+    FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_with_codec()
+        .unwrap()
+}
+
+#[test]
+fn custom_codec_upsert_read() {
+    let store = new_store();
+    let key: u64 = 1;
+    let value = String::from("value");
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+}
+
+#[test]
+fn custom_codec_rmw() {
+    let store = new_store();
+    let session = store.start_session();
+    let key: u64 = 1;
+
+    session.upsert(&key, &(10 as u64));
+    let rmw = session.rmw(&key, &(5 as u64));
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    session.complete_pending(true);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&key);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(15));
+}
+
+#[test]
+fn custom_codec_typed_store() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_typed_with_codec::<u64, Vec<u64>, Versioned>()
+        .unwrap();
+    let key: u64 = 1;
+
+    store.upsert(&key, &vec![1, 2], 1);
+    store.rmw(&key, &vec![3], 1);
+    let (res, val) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(val.unwrap(), Some(vec![1, 2, 3]));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_codec_upsert_read() {
+    use std::collections::HashMap;
+
+    let store: FasterKv<faster_rs::Json> = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_with_codec()
+        .unwrap();
+    let key = String::from("config");
+    let mut value = HashMap::new();
+    value.insert(String::from("retries"), 3 as u64);
+
+    store.upsert(&key, &value, 1);
+    let (res, recv): (u8, Receiver<ReadResult<HashMap<String, u64>>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_codec_reports_encoding_errors() {
+    use std::collections::HashMap;
+
+    let store: FasterKv<faster_rs::Json> = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_with_codec()
+        .unwrap();
+    // JSON object keys must be strings
+    let mut value = HashMap::new();
+    value.insert(vec![1 as u8], 1 as u64);
+
+    match store.try_upsert(&(1 as u64), &value, 1) {
+        Err(FasterError::SerializationError(_)) => assert!(true),
+        _ => assert!(false, "Should give SerializationError"),
+    }
+}
@@ -0,0 +1,186 @@
+extern crate faster_rs;
+extern crate tempfile;
+
+use faster_rs::{status, CompactionPolicy, FasterError, FasterKv, FasterKvBuilder, ReadResult};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+// A log of eight 32 MB pages, two of which are mutable, so that the records written by a
+// test leave the mutable region once a few pages of filler follow them
+const TABLE_SIZE: u64 = 1 << 15;
+const LOG_SIZE: u64 = 256 * 1024 * 1024;
+const LOG_MUTABLE_FRACTION: f64 = 0.25;
+
+// Keys of the filler records, clear of those written by write_and_overwrite
+const FILLER_KEYS: u64 = 1 << 32;
+
+fn small_log_builder(dir_path: &str) -> FasterKvBuilder<'_> {
+    let mut builder = FasterKvBuilder::new(TABLE_SIZE, LOG_SIZE);
+    builder
+        .with_disk(dir_path)
+        .with_log_mutable_fraction(LOG_MUTABLE_FRACTION);
+    builder
+}
+
+// Appends filler records until every record before `address` has left the mutable
+// region, then deletes them again
+fn move_out_of_mutable_region(store: &FasterKv, address: u64) {
+    let session = store.start_session();
+    let filler = vec![0u8; 64 * 1024];
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let mut key = FILLER_KEYS;
+    while store.log_safe_read_only_address() < address {
+        assert!(
+            Instant::now() < deadline,
+            "log did not leave the mutable region"
+        );
+        session.upsert(&key, &filler);
+        // The read-only address only moves on once the session refreshes
+        session.refresh();
+        key += 1;
+    }
+    for key in FILLER_KEYS..key {
+        session.delete(&key);
+    }
+    session.complete_pending(true);
+}
+
+// Overwrites the even keys and deletes every tenth key, returning the expected contents
+fn write_and_overwrite(store: &FasterKv) -> HashMap<u64, u64> {
+    let session = store.start_session();
+    let mut expected = HashMap::new();
+    for key in 0..1000 {
+        session.upsert(&(key as u64), &(key as u64));
+        expected.insert(key, key);
+    }
+    for key in (0..1000).step_by(2) {
+        session.upsert(&(key as u64), &(key as u64 * 2));
+        expected.insert(key, key * 2);
+    }
+    for key in (0..1000).step_by(10) {
+        session.delete(&(key as u64));
+        expected.remove(&key);
+    }
+    session.complete_pending(true);
+    expected
+}
+
+fn assert_reads(store: &FasterKv, expected: &HashMap<u64, u64>) {
+    let session = store.start_session();
+    for key in 0..1000 {
+        let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(key as u64));
+        session.complete_pending(true);
+        match expected.get(&key) {
+            Some(value) => assert_eq!(recv.recv().unwrap().unwrap(), Some(*value)),
+            None => assert_eq!(res, status::NOT_FOUND),
+        }
+    }
+}
+
+fn assert_contents(store: &FasterKv, expected: &HashMap<u64, u64>) {
+    assert_reads(store, expected);
+    let records: HashMap<u64, u64> = store.iter().map(|record| record.unwrap()).collect();
+    assert_eq!(&records, expected);
+}
+
+#[test]
+fn compact_preserves_live_records() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = small_log_builder(&dir_path).build().unwrap();
+    let expected = write_and_overwrite(&store);
+    move_out_of_mutable_region(&store, store.log_tail_address());
+
+    let until_address = store.log_safe_read_only_address();
+    store.compact(until_address).unwrap();
+    assert_eq!(store.log_begin_address(), until_address);
+    assert_contents(&store, &expected);
+}
+
+#[test]
+fn compact_past_tail() {
+    let store = FasterKv::default();
+    store.upsert(&(1 as u64), &(1 as u64), 1);
+    match store.compact(store.log_tail_address() + (1 << 20)) {
+        Err(FasterError::CompactionError) => assert!(true),
+        _ => assert!(false, "Should give CompactionError"),
+    }
+}
+
+#[test]
+fn compact_mutable_region() {
+    let store = FasterKv::default();
+    write_and_overwrite(&store);
+    assert_eq!(
+        store.log_safe_read_only_address(),
+        store.log_begin_address()
+    );
+    match store.compact(store.log_tail_address()) {
+        Err(FasterError::CompactionError) => {}
+        _ => panic!("Should give CompactionError"),
+    }
+}
+
+#[test]
+fn compaction_survives_recovery() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+
+    let expected = {
+        let store = small_log_builder(&dir_path).build().unwrap();
+        let expected = write_and_overwrite(&store);
+        move_out_of_mutable_region(&store, store.log_tail_address());
+        store.compact(store.log_safe_read_only_address()).unwrap();
+        assert!(store.checkpoint().unwrap().checked);
+        expected
+    };
+
+    let (store, recover) = small_log_builder(&dir_path).open_or_recover().unwrap();
+    assert!(recover.is_some());
+    assert_contents(&store, &expected);
+}
+
+#[test]
+fn compaction_policy_compacts_in_background() {
+    let (sender, receiver) = channel();
+    let sender = Mutex::new(sender);
+    let policy = CompactionPolicy::on_log_size(100)
+        .with_fraction(0.5)
+        .with_check_interval(Duration::from_millis(10))
+        .on_compacted(move |record| {
+            let _ = sender.lock().unwrap().send(record.clone());
+        });
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let store = small_log_builder(&dir_path)
+        .with_compaction_policy(policy)
+        .build()
+        .unwrap();
+    let begin_address = store.log_begin_address();
+    let expected = write_and_overwrite(&store);
+    // Nothing can be compacted until the records leave the mutable region
+    move_out_of_mutable_region(&store, store.log_tail_address());
+
+    let record = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert!(record.succeeded);
+    assert_eq!(record.from_address, begin_address);
+    assert!(record.until_address > begin_address);
+    assert!(record.until_address <= store.log_safe_read_only_address());
+    assert!(store.log_begin_address() >= record.until_address);
+    // The policy keeps compacting while the log is over its limit, and a scan may miss
+    // the records being moved, so only reads are checked
+    assert_reads(&store, &expected);
+}
+
+#[test]
+fn compaction_fraction_must_be_valid() {
+    let mut builder = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024);
+    builder.with_compaction_policy(CompactionPolicy::on_log_size(100).with_fraction(1.5));
+    match builder.build() {
+        Err(FasterError::BuilderError(_)) => assert!(true),
+        _ => assert!(false, "Should give BuilderError"),
+    }
+}
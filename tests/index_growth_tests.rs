@@ -0,0 +1,78 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterError, FasterKvBuilder, IndexGrowthPolicy, ReadResult};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[test]
+fn index_grows_in_background() {
+    let (sender, receiver) = channel();
+    let sender = Mutex::new(sender);
+    let policy = IndexGrowthPolicy::on_load_factor(0.9)
+        .with_check_interval(Duration::from_millis(10))
+        .on_grown(move |record| {
+            let _ = sender.lock().unwrap().send(record.clone());
+        });
+    let store = FasterKvBuilder::new(1 << 8, 1024 * 1024 * 1024)
+        .with_index_growth_policy(policy)
+        .build()
+        .unwrap();
+    let session = store.start_session();
+    let items: Vec<(u64, u64)> = (0..10000).map(|key| (key, key * 2)).collect();
+    session.upsert_batch(items.iter().map(|(key, value)| (key, value)));
+    session.complete_pending(true);
+
+    // The index only finishes growing once the session refreshes
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let record = loop {
+        session.refresh();
+        match receiver.recv_timeout(Duration::from_millis(10)) {
+            Ok(record) => break record,
+            Err(_) => assert!(Instant::now() < deadline, "index did not grow"),
+        }
+    };
+    assert!(record.succeeded);
+    assert!(record.load_factor > 0.9);
+    assert_eq!(record.table_size_before, 1 << 8);
+    assert_eq!(record.table_size_after, 1 << 9);
+    assert!(store.index_distribution().table_size >= 1 << 9);
+
+    for (key, value) in items.iter().step_by(100) {
+        let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(key);
+        assert!(res == status::OK || res == status::PENDING);
+        session.complete_pending(true);
+        assert_eq!(recv.recv().unwrap().unwrap(), Some(*value));
+    }
+}
+
+#[test]
+fn index_is_not_grown_below_load_factor() {
+    let (sender, receiver) = channel();
+    let sender = Mutex::new(sender);
+    let policy = IndexGrowthPolicy::on_load_factor(0.9)
+        .with_check_interval(Duration::from_millis(10))
+        .on_grown(move |record| {
+            let _ = sender.lock().unwrap().send(record.clone());
+        });
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .with_index_growth_policy(policy)
+        .build()
+        .unwrap();
+    for key in 0..100 {
+        store.upsert(&(key as u64), &(key as u64), 1);
+    }
+
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    assert_eq!(store.index_distribution().table_size, 1 << 15);
+}
+
+#[test]
+fn index_growth_load_factor_must_be_positive() {
+    let mut builder = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024);
+    builder.with_index_growth_policy(IndexGrowthPolicy::on_load_factor(0.0));
+    match builder.build() {
+        Err(FasterError::BuilderError(_)) => assert!(true),
+        _ => assert!(false, "Should give BuilderError"),
+    }
+}
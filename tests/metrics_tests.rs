@@ -0,0 +1,173 @@
+#![cfg(feature = "metrics")]
+extern crate faster_rs;
+extern crate metrics;
+extern crate metrics_util;
+extern crate tempfile;
+
+use faster_rs::{status, FasterKv, FasterKvBuilder};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use metrics_util::CompositeKey;
+use tempfile::TempDir;
+
+// Histogram samples are drained by every snapshot, so each test takes a single one
+fn snapshot(snapshotter: &Snapshotter) -> Vec<(CompositeKey, DebugValue)> {
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| (key, value))
+        .collect()
+}
+
+fn counter(metrics: &[(CompositeKey, DebugValue)], name: &str, labels: &[(&str, &str)]) -> u64 {
+    metrics
+        .iter()
+        .filter(|(key, _)| {
+            key.key().name() == name
+                && labels.iter().all(|(label, value)| {
+                    key.key()
+                        .labels()
+                        .any(|l| l.key() == *label && l.value() == *value)
+                })
+        })
+        .map(|(_, value)| match value {
+            DebugValue::Counter(count) => *count,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn histogram_samples(metrics: &[(CompositeKey, DebugValue)], name: &str) -> usize {
+    metrics
+        .iter()
+        .filter(|(key, _)| key.key().name() == name)
+        .map(|(_, value)| match value {
+            DebugValue::Histogram(samples) => samples.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[test]
+fn operations_are_counted_by_status() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let store = FasterKv::default();
+        for key in 0..10u64 {
+            let upsert = store.upsert(&key, &key, 1);
+            assert!(upsert == status::OK || upsert == status::PENDING);
+        }
+        let (read, _) = store.read::<u64, u64>(&100, 1);
+        assert_eq!(read, status::NOT_FOUND);
+        store.delete(&1u64, 1);
+        store.complete_pending(true);
+    });
+    let metrics = snapshot(&snapshotter);
+
+    let upserts = &[("operation", "upsert")];
+    assert_eq!(counter(&metrics, "faster_operations_total", upserts), 10);
+    assert_eq!(
+        counter(
+            &metrics,
+            "faster_operations_total",
+            &[("operation", "read"), ("status", "not_found")]
+        ),
+        1
+    );
+    assert_eq!(
+        counter(
+            &metrics,
+            "faster_operations_total",
+            &[("operation", "delete")]
+        ),
+        1
+    );
+    assert_eq!(histogram_samples(&metrics, "faster_operation_seconds"), 12);
+    assert_eq!(
+        histogram_samples(&metrics, "faster_complete_pending_seconds"),
+        1
+    );
+}
+
+#[test]
+fn batch_operations_are_counted_individually() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let store = FasterKv::default();
+        let session = store.start_session();
+        let items: Vec<(u64, u64)> = (0..100).map(|key| (key, key)).collect();
+        session.upsert_batch(items.iter().map(|(key, value)| (key, value)));
+        session.complete_pending(true);
+    });
+    let metrics = snapshot(&snapshotter);
+
+    assert_eq!(
+        counter(
+            &metrics,
+            "faster_operations_total",
+            &[("operation", "upsert")]
+        ),
+        100
+    );
+}
+
+#[test]
+fn checkpoints_and_recovery_are_counted() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir_path = tmp_dir.path().to_string_lossy().into_owned();
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        let token = {
+            let store = FasterKvBuilder::new(1 << 14, 1024 * 1024 * 1024)
+                .with_disk(&dir_path)
+                .build()
+                .unwrap();
+            let session = store.start_session();
+            session.upsert(&1u64, &1u64);
+            let checkpoint = store.checkpoint().unwrap();
+            assert!(checkpoint.checked);
+            store.checkpoint_index().unwrap();
+            checkpoint.token
+        };
+
+        let store = FasterKvBuilder::new(1 << 14, 1024 * 1024 * 1024)
+            .with_disk(&dir_path)
+            .build()
+            .unwrap();
+        store.recover(token.clone(), token).unwrap();
+    });
+    let metrics = snapshot(&snapshotter);
+
+    assert_eq!(
+        counter(
+            &metrics,
+            "faster_checkpoints_total",
+            &[("kind", "full"), ("result", "started")]
+        ),
+        1
+    );
+    assert_eq!(
+        counter(&metrics, "faster_checkpoints_total", &[("kind", "index")]),
+        1
+    );
+    assert_eq!(histogram_samples(&metrics, "faster_checkpoint_seconds"), 2);
+    assert_eq!(
+        counter(&metrics, "faster_recoveries_total", &[("result", "ok")]),
+        1
+    );
+    assert_eq!(histogram_samples(&metrics, "faster_recovery_seconds"), 1);
+}
+
+#[test]
+fn operations_outside_a_recorder_are_not_counted() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let store = FasterKv::default();
+    store.upsert(&1u64, &1u64, 1);
+    let metrics = snapshot(&snapshotter);
+
+    assert_eq!(counter(&metrics, "faster_operations_total", &[]), 0);
+}
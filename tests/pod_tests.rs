@@ -0,0 +1,91 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterError, FasterKv, ReadResult};
+use std::sync::mpsc::channel;
+
+fn read_pod<V: faster_rs::Pod>(store: &FasterKv, key: &u64) -> (u8, ReadResult<V>) {
+    let (sender, receiver) = channel();
+    let res = store.read_pod(key, 1, move |result| sender.send(result).unwrap());
+    store.complete_pending(true);
+    (res, receiver.recv().unwrap())
+}
+
+#[test]
+fn upsert_read_pod() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+
+    let upsert = store.upsert_pod(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, read) = read_pod::<u64>(&store, &key);
+    assert!(res == status::OK || res == status::PENDING);
+    assert_eq!(read.unwrap(), Some(value));
+}
+
+#[test]
+fn read_pod_missing_key() {
+    let store = FasterKv::default();
+    let (res, read) = read_pod::<u64>(&store, &1);
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(read.unwrap(), None);
+}
+
+#[test]
+fn rmw_pod_adds() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    // The modification becomes the initial value of a missing key
+    store.rmw_pod(&key, &(10 as u64), 1);
+    let rmw = store.rmw_pod(&key, &(5 as u64), 1);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (_, read) = read_pod::<u64>(&store, &key);
+    assert_eq!(read.unwrap(), Some(15));
+}
+
+#[test]
+fn delete_pod() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert_pod(&key, &[1u32, 2, 3], 1);
+    let delete = store.delete_pod(&key, 1);
+    assert!(delete == status::OK || delete == status::PENDING);
+
+    let (_, read) = read_pod::<[u32; 3]>(&store, &key);
+    assert_eq!(read.unwrap(), None);
+}
+
+#[test]
+fn read_pod_with_wrong_size() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert_pod(&key, &(1 as u32), 1);
+    let (_, read) = read_pod::<u64>(&store, &key);
+    match read {
+        Err(FasterError::DeserializationError(_)) => assert!(true),
+        _ => assert!(false, "Should give DeserializationError"),
+    }
+}
+
+#[test]
+fn session_pod_operations() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+
+    session.upsert_pod(&key, &(1.5 as f64));
+    session.rmw_pod(&key, &(2.0 as f64));
+
+    let (sender, receiver) = channel();
+    session.read_pod(&key, move |result: ReadResult<f64>| {
+        sender.send(result).unwrap()
+    });
+    session.complete_pending(true);
+    assert_eq!(receiver.recv().unwrap().unwrap(), Some(3.5));
+    assert_eq!(session.serial_number(), 3);
+}
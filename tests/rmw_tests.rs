@@ -0,0 +1,388 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterKv, FasterKvBuilder, FasterRmw, Pod, ReadResult};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn rmw_with_initializes_missing_key() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    let rmw = store.rmw_with(&key, &(5 as u64), 1, |current: Option<&u64>, input: u64| {
+        current.map_or(100, |current| current * input)
+    });
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    let rmw = store.rmw_with(&key, &(5 as u64), 1, |current: Option<&u64>, input: u64| {
+        current.map_or(100, |current| current * input)
+    });
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(500));
+}
+
+#[test]
+fn rmw_with_input_type_differs_from_value() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key = String::from("word-counts");
+
+    for word in &["apple", "pear", "apple"] {
+        session.rmw_with(
+            &key,
+            &String::from(*word),
+            |current: Option<&HashMap<String, u64>>, word: String| {
+                let mut counts = current.cloned().unwrap_or_default();
+                *counts.entry(word).or_insert(0) += 1;
+                counts
+            },
+        );
+    }
+    session.complete_pending(true);
+
+    let (_, recv): (u8, Receiver<ReadResult<HashMap<String, u64>>>) = session.read(&key);
+    let counts = recv.recv().unwrap().unwrap().unwrap();
+    assert_eq!(counts.get("apple"), Some(&2));
+    assert_eq!(counts.get("pear"), Some(&1));
+}
+
+#[test]
+fn rmw_with_keeps_value_of_wrong_type() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let value = String::from("value");
+
+    store.upsert(&key, &value, 1);
+    store.rmw_with(&key, &(1 as u8), 1, |current: Option<&Vec<u64>>, _: u8| {
+        current.cloned().unwrap_or_default()
+    });
+
+    let (_, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(value));
+}
+
+#[test]
+fn typed_rmw_with() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_typed::<u64, String>()
+        .unwrap();
+    let key: u64 = 1;
+
+    store.rmw_with(&key, &(3 as u64), 1, |current, times: u64| {
+        current.cloned().unwrap_or_default() + &"ab".repeat(times as usize)
+    });
+    let (res, value) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(value.unwrap(), Some(String::from("ababab")));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Average {
+    count: u64,
+    sum: u64,
+}
+
+impl FasterRmw for Average {
+    fn rmw(&self, modification: Self) -> Self {
+        Average {
+            count: self.count + 1,
+            sum: self.sum + modification.sum,
+        }
+    }
+
+    fn initial(modification: Self) -> Self {
+        Average {
+            count: 1,
+            sum: modification.sum,
+        }
+    }
+}
+
+#[test]
+fn rmw_initial_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    for sample in &[10, 20] {
+        let rmw = store.rmw(
+            &key,
+            &Average {
+                count: 0,
+                sum: *sample,
+            },
+            1,
+        );
+        assert!(rmw == status::OK || rmw == status::PENDING);
+    }
+
+    let (_, recv): (u8, Receiver<ReadResult<Average>>) = store.read(&key, 1);
+    assert_eq!(
+        recv.recv().unwrap().unwrap(),
+        Some(Average { count: 2, sum: 30 })
+    );
+}
+
+static IN_PLACE_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Counter(u64);
+
+impl FasterRmw for Counter {
+    fn rmw(&self, modification: Self) -> Self {
+        Counter(self.0 + modification.0)
+    }
+
+    fn rmw_in_place(&mut self, modification: Self) -> bool {
+        IN_PLACE_UPDATES.fetch_add(1, Ordering::SeqCst);
+        self.0 += modification.0;
+        true
+    }
+}
+
+#[test]
+fn rmw_in_place_update() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert(&key, &Counter(1), 1);
+    let rmw = store.rmw(&key, &Counter(2), 1);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    assert!(IN_PLACE_UPDATES.load(Ordering::SeqCst) >= 1);
+
+    let (_, recv): (u8, Receiver<ReadResult<Counter>>) = store.read(&key, 1);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(Counter(3)));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Text(String);
+
+impl FasterRmw for Text {
+    fn rmw(&self, modification: Self) -> Self {
+        Text(self.0.clone() + &modification.0)
+    }
+
+    // Grows the encoding, so FASTER has to fall back to a copy update
+    fn rmw_in_place(&mut self, modification: Self) -> bool {
+        self.0.push_str(&modification.0);
+        true
+    }
+}
+
+#[test]
+fn rmw_in_place_falls_back_when_size_changes() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert(&key, &Text(String::from("Hello, ")), 1);
+    store.rmw(&key, &Text(String::from("World!")), 1);
+
+    let (_, recv): (u8, Receiver<ReadResult<Text>>) = store.read(&key, 1);
+    assert_eq!(
+        recv.recv().unwrap().unwrap(),
+        Some(Text(String::from("Hello, World!")))
+    );
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+struct MinMax {
+    min: u64,
+    max: u64,
+}
+
+unsafe impl Pod for MinMax {}
+
+impl FasterRmw for MinMax {
+    fn rmw(&self, modification: Self) -> Self {
+        MinMax {
+            min: self.min.min(modification.min),
+            max: self.max.max(modification.max),
+        }
+    }
+
+    fn initial(modification: Self) -> Self {
+        MinMax {
+            min: modification.min,
+            max: modification.min,
+        }
+    }
+}
+
+#[test]
+fn rmw_pod_initial_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.rmw_pod(&key, &MinMax { min: 5, max: 0 }, 1);
+    store.rmw_pod(&key, &MinMax { min: 7, max: 7 }, 1);
+
+    let (sender, receiver) = channel();
+    store.read_pod(&key, 1, move |result: ReadResult<MinMax>| {
+        sender.send(result).unwrap()
+    });
+    store.complete_pending(true);
+    assert_eq!(
+        receiver.recv().unwrap().unwrap(),
+        Some(MinMax { min: 5, max: 7 })
+    );
+}
+
+#[test]
+fn compare_and_swap_matching_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let pending = String::from("pending");
+    let running = String::from("running");
+
+    store.upsert(&key, &pending, 1);
+    let (swapped, observed) = store
+        .compare_and_swap(&key, Some(&pending), &running, 1)
+        .unwrap();
+    assert!(swapped);
+    assert_eq!(observed, Some(pending));
+
+    let (_, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(running));
+}
+
+#[test]
+fn compare_and_swap_different_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+    let done = String::from("done");
+
+    store.upsert(&key, &done, 1);
+    let (swapped, observed) = store
+        .compare_and_swap(
+            &key,
+            Some(&String::from("pending")),
+            &String::from("running"),
+            1,
+        )
+        .unwrap();
+    assert!(!swapped);
+    assert_eq!(observed, Some(done.clone()));
+
+    let (_, recv): (u8, Receiver<ReadResult<String>>) = store.read(&key, 1);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(done));
+}
+
+#[test]
+fn compare_and_swap_missing_key() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    let key: u64 = 1;
+
+    // Expecting a value does not create the key
+    let (swapped, observed) = session
+        .compare_and_swap(&key, Some(&(1 as u64)), &(2 as u64))
+        .unwrap();
+    assert!(!swapped);
+    assert_eq!(observed, None);
+    let (res, _): (u8, Receiver<ReadResult<u64>>) = session.read(&key);
+    assert_eq!(res, status::NOT_FOUND);
+
+    // Expecting no value inserts it
+    let (swapped, observed) = session.compare_and_swap(&key, None, &(2 as u64)).unwrap();
+    assert!(swapped);
+    assert_eq!(observed, None);
+    let (swapped, observed) = session.compare_and_swap(&key, None, &(3 as u64)).unwrap();
+    assert!(!swapped);
+    assert_eq!(observed, Some(2));
+}
+
+#[test]
+fn typed_compare_and_swap() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_typed::<u64, u64>()
+        .unwrap();
+    let key: u64 = 1;
+
+    store.upsert(&key, &1, 1);
+    assert_eq!(
+        store.compare_and_swap(&key, Some(&1), &2, 1).unwrap(),
+        (true, Some(1))
+    );
+    let (_, value) = store.read(&key, 1);
+    assert_eq!(value.unwrap(), Some(2));
+}
+
+#[test]
+fn rmw_fetch_before_and_after() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    let (res, recv) = store.rmw_fetch(&key, &(5 as u64), 1);
+    assert!(res == status::OK || res == status::PENDING);
+    store.complete_pending(true);
+    let fetched = recv.recv().unwrap().unwrap();
+    assert_eq!(fetched.before, None);
+    assert_eq!(fetched.after, 5);
+
+    let (_, recv) = store.rmw_fetch(&key, &(5 as u64), 1);
+    store.complete_pending(true);
+    let fetched = recv.recv().unwrap().unwrap();
+    assert_eq!(fetched.before, Some(5));
+    assert_eq!(fetched.after, 10);
+}
+
+#[test]
+fn rmw_fetch_uses_initial_value() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    let (_, recv) = store.rmw_fetch(&key, &MinMax { min: 3, max: 0 }, 1);
+    store.complete_pending(true);
+    assert_eq!(
+        recv.recv().unwrap().unwrap().after,
+        MinMax { min: 3, max: 3 }
+    );
+}
+
+#[test]
+fn rmw_fetch_wrong_type() {
+    let store = FasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert(&key, &String::from("value"), 1);
+    let (_, recv) = store.rmw_fetch(&key, &vec![1 as u64], 1);
+    store.complete_pending(true);
+    assert!(recv.recv().unwrap().is_err());
+}
+
+#[test]
+fn rmw_fetch_hands_out_unique_ids() {
+    let store = Arc::new(FasterKv::default());
+    let key = String::from("next-id");
+    let ids_per_thread = 1000;
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let store = Arc::clone(&store);
+            let key = key.clone();
+            thread::spawn(move || {
+                let session = store.start_session();
+                (0..ids_per_thread)
+                    .map(|_| {
+                        let (_, recv) = session.rmw_fetch(&key, &(1 as u64));
+                        session.complete_pending(true);
+                        recv.recv().unwrap().unwrap().after
+                    })
+                    .collect::<Vec<u64>>()
+            })
+        })
+        .collect();
+
+    let mut ids: Vec<u64> = threads
+        .into_iter()
+        .flat_map(|thread| thread.join().unwrap())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 4 * ids_per_thread);
+}
@@ -0,0 +1,92 @@
+extern crate faster_rs;
+
+use faster_rs::{FasterKv, FasterKvBuilder};
+use std::collections::HashMap;
+
+#[test]
+fn iter_empty_store() {
+    let store = FasterKv::default();
+    assert_eq!(store.iter::<u64, u64>().count(), 0);
+}
+
+#[test]
+fn iter_returns_latest_versions() {
+    let store = FasterKv::default();
+    for i in 0..100 {
+        store.upsert(&(i as u64), &(i as u64), 1);
+    }
+    // Supersede half of the keys, once by Upsert and once by RMW
+    for i in 0..50 {
+        store.upsert(&(i as u64), &(i as u64 * 10), 1);
+        store.rmw(&(i as u64), &(1 as u64), 1);
+    }
+    store.complete_pending(true);
+
+    let records: HashMap<u64, u64> = store
+        .iter::<u64, u64>()
+        .map(|record| record.unwrap())
+        .collect();
+    assert_eq!(records.len(), 100);
+    for i in 0..100 {
+        let expected = if i < 50 { i * 10 + 1 } else { i };
+        assert_eq!(records[&i], expected);
+    }
+    // Every key is visited once
+    assert_eq!(store.iter::<u64, u64>().count(), 100);
+}
+
+#[test]
+fn iter_skips_deleted_keys() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    session.upsert(&String::from("kept"), &1u64);
+    session.upsert(&String::from("deleted"), &2u64);
+    session.upsert(&String::from("reinserted"), &3u64);
+    session.delete(&String::from("deleted"));
+    session.delete(&String::from("reinserted"));
+    session.upsert(&String::from("reinserted"), &4u64);
+    session.complete_pending(true);
+
+    let mut records: Vec<(String, u64)> = store
+        .iter::<String, u64>()
+        .map(|record| record.unwrap())
+        .collect();
+    records.sort();
+    assert_eq!(
+        records,
+        vec![(String::from("kept"), 1), (String::from("reinserted"), 4)]
+    );
+}
+
+#[test]
+fn scan_raw_bytes() {
+    let store = FasterKv::default();
+    store.upsert_bytes(b"key", b"first", 1);
+    store.upsert_bytes(b"key", b"second", 1);
+
+    let records: Vec<(Vec<u8>, Vec<u8>)> = store.scan().collect();
+    assert_eq!(records, vec![(b"key".to_vec(), b"second".to_vec())]);
+}
+
+#[test]
+fn iter_reports_records_of_another_type() {
+    let store = FasterKv::default();
+    store.upsert(&(1 as u64), &String::from("value"), 1);
+
+    let records: Vec<_> = store.iter::<u64, Vec<u64>>().collect();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].is_err());
+}
+
+#[test]
+fn typed_iter() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_typed::<u64, String>()
+        .unwrap();
+    store.upsert(&1, &String::from("one"), 1);
+    store.upsert(&2, &String::from("two"), 1);
+    store.delete(&1, 1);
+
+    let records: Vec<(u64, String)> = store.iter().map(|record| record.unwrap()).collect();
+    assert_eq!(records, vec![(2, String::from("two"))]);
+}
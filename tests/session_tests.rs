@@ -0,0 +1,58 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterError, FasterKv, ReadResult};
+use std::sync::mpsc::Receiver;
+
+#[test]
+fn session_numbers_operations() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+    assert_eq!(session.persisted_serial_number(), 0);
+    assert_eq!(session.serial_number(), 0);
+
+    let upsert = session.upsert(&(1 as u64), &(1337 as u64));
+    assert!(upsert == status::OK || upsert == status::PENDING);
+    assert_eq!(session.serial_number(), 1);
+
+    let rmw = session.rmw(&(1 as u64), &(5 as u64));
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    assert_eq!(session.serial_number(), 2);
+
+    let (res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(1 as u64));
+    assert_eq!(res, status::OK);
+    assert_eq!(recv.recv().unwrap().unwrap(), Some(1342));
+    assert_eq!(session.serial_number(), 3);
+
+    let delete = session.delete(&(1 as u64));
+    assert!(delete == status::OK || delete == status::PENDING);
+    assert_eq!(session.serial_number(), 4);
+}
+
+#[test]
+fn session_continues_from_explicit_serial() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+
+    session
+        .upsert_with_serial(&(1 as u64), &(1337 as u64), 100)
+        .unwrap();
+    assert_eq!(session.serial_number(), 100);
+
+    session.upsert(&(2 as u64), &(1337 as u64));
+    assert_eq!(session.serial_number(), 101);
+}
+
+#[test]
+fn session_rejects_lower_explicit_serial() {
+    let store = FasterKv::default();
+    let session = store.start_session();
+
+    session.upsert_with_serial(&1u64, &1337u64, 100).unwrap();
+    match session.delete_with_serial(&1u64, 100) {
+        Err(FasterError::SerialNumberError(100, 100)) => {}
+        other => panic!("expected a serial number error, got {:?}", other),
+    }
+    assert_eq!(session.serial_number(), 100);
+    let (res, _): (u8, Receiver<ReadResult<u64>>) = session.read(&1u64);
+    assert_eq!(res, status::OK);
+}
@@ -0,0 +1,99 @@
+extern crate faster_rs;
+
+use faster_rs::{FasterKv, FasterKvBuilder, LogStats};
+use std::time::{Duration, Instant};
+
+fn assert_regions_ordered(stats: &LogStats) {
+    assert!(stats.begin_address <= stats.head_address);
+    assert!(stats.head_address <= stats.safe_read_only_address);
+    assert!(stats.safe_read_only_address <= stats.read_only_address);
+    assert!(stats.read_only_address <= stats.tail_address);
+    assert_eq!(
+        stats.on_disk_bytes + stats.in_memory_bytes(),
+        stats.tail_address - stats.begin_address
+    );
+}
+
+#[test]
+fn log_stats_empty_store() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build()
+        .unwrap();
+    let stats = store.log_stats();
+    assert_regions_ordered(&stats);
+    assert_eq!(stats.index.table_size, 1 << 15);
+    assert_eq!(stats.index.entries, 0);
+    assert_eq!(stats.index.occupancy(), 0.0);
+}
+
+#[test]
+fn log_stats_grow_with_writes() {
+    let store = FasterKv::default();
+    let before = store.log_stats();
+    for key in 0..1000 {
+        store.upsert(&(key as u64), &String::from("value"), 1);
+    }
+
+    let after = store.log_stats();
+    assert_regions_ordered(&after);
+    assert!(after.tail_address > before.tail_address);
+    assert!(after.index.entries > 0);
+    assert!(after.index.used_buckets <= after.index.table_size);
+    assert!(after.index.occupancy() > 0.0 && after.index.occupancy() <= 1.0);
+}
+
+#[test]
+fn log_stats_after_compaction() {
+    let store = FasterKv::default();
+    for key in 0..1000 {
+        store.upsert(&(key as u64), &(key as u64), 1);
+    }
+    let stats = store.log_stats();
+    store.compact(stats.safe_read_only_address).unwrap();
+
+    let compacted = store.log_stats();
+    assert_regions_ordered(&compacted);
+    assert_eq!(compacted.begin_address, stats.safe_read_only_address);
+    assert_eq!(compacted.begin_address, store.log_begin_address());
+    assert_eq!(compacted.tail_address, store.log_tail_address());
+}
+
+#[test]
+fn index_distribution_empty_store() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build()
+        .unwrap();
+    let distribution = store.index_distribution();
+    assert_eq!(distribution.table_size, 1 << 15);
+    assert_eq!(distribution.entries, 0);
+    assert_eq!(distribution.histogram[0], 1 << 15);
+    assert_eq!(distribution.load_factor(), 0.0);
+    assert!(!distribution.should_grow(0.5));
+    assert!(!store.grow_index_if_needed(0.5));
+}
+
+#[test]
+fn index_distribution_of_full_table() {
+    let store = FasterKvBuilder::new(1 << 8, 1024 * 1024 * 1024)
+        .build()
+        .unwrap();
+    let session = store.start_session();
+    for key in 0..10000 {
+        session.upsert(&(key as u64), &(key as u64));
+    }
+
+    let distribution = store.index_distribution();
+    assert_eq!(distribution.histogram.iter().sum::<u64>(), 1 << 8);
+    assert!(distribution.entries > 0);
+    assert!(distribution.load_factor() > 1.0);
+    assert!(distribution.overflowing_buckets() > 0);
+    assert!(distribution.should_grow(0.9));
+
+    assert!(store.grow_index_if_needed(0.9));
+    // The index only finishes growing once the session refreshes
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while store.index_distribution().table_size != 1 << 9 {
+        assert!(Instant::now() < deadline, "index did not grow");
+        session.refresh();
+    }
+}
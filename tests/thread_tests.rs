@@ -1,6 +1,6 @@
 extern crate faster_rs;
 
-use faster_rs::FasterKv;
+use faster_rs::{FasterKv, ReadResult};
 use std::sync::mpsc::Receiver;
 use std::sync::Arc;
 use std::thread;
@@ -12,10 +12,10 @@ fn multi_threaded_test() {
 
     let initial_value: u64 = 100;
     let modification: u64 = 30;
-    store.start_session();
+    let session = store.start_session();
 
     for key in 0..ops {
-        store.upsert(&(key as u64), &initial_value, key);
+        session.upsert(&(key as u64), &initial_value);
     }
 
     let num_threads = 16;
@@ -23,18 +23,13 @@ fn multi_threaded_test() {
     for _ in 0..num_threads {
         let store = Arc::clone(&store);
         threads.push(thread::spawn(move || {
-            // Register FASTER thread
-            let _session = store.start_session();
+            // Register FASTER thread, dropping the session completes pending
+            // operations and unregisters it
+            let session = store.start_session();
 
             for key in 0..ops {
-                store.rmw(&(key as u64), &modification, key);
+                session.rmw(&(key as u64), &modification);
             }
-
-            // Make sure everything is completed
-            store.complete_pending(true);
-
-            // Unregister Thread
-            store.stop_session();
         }))
     }
 
@@ -44,9 +39,7 @@ fn multi_threaded_test() {
 
     for key in 0..ops {
         let expected_value = initial_value + (modification * num_threads);
-        let (_res, recv): (u8, Receiver<u64>) = store.read(&key, ops + key);
-        assert_eq!(recv.recv().unwrap(), expected_value);
+        let (_res, recv): (u8, Receiver<ReadResult<u64>>) = session.read(&(key as u64));
+        assert_eq!(recv.recv().unwrap().unwrap(), Some(expected_value));
     }
-    store.complete_pending(true);
-    store.stop_session();
 }
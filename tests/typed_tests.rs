@@ -0,0 +1,88 @@
+extern crate faster_rs;
+
+use faster_rs::{status, FasterKvBuilder, TypedFasterKv};
+
+#[test]
+fn typed_read_inserted_value() {
+    let store: TypedFasterKv<u64, u64> = TypedFasterKv::default();
+    let key: u64 = 1;
+    let value: u64 = 1337;
+
+    let upsert = store.upsert(&key, &value, 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let (res, val) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(val.unwrap(), Some(value));
+}
+
+#[test]
+fn typed_read_missing_value() {
+    let store: TypedFasterKv<u64, String> = TypedFasterKv::default();
+    let key: u64 = 1;
+
+    let (res, val) = store.read(&key, 1);
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(val.unwrap(), None);
+}
+
+#[test]
+fn typed_rmw_string() {
+    let store = FasterKvBuilder::new(1 << 15, 1024 * 1024 * 1024)
+        .build_typed::<String, String>()
+        .unwrap();
+    let key = String::from("greeting");
+
+    let upsert = store.upsert(&key, &String::from("Hello, "), 1);
+    assert!(upsert == status::OK || upsert == status::PENDING);
+
+    let rmw = store.rmw(&key, &String::from("World!"), 1);
+    assert!(rmw == status::OK || rmw == status::PENDING);
+
+    let (res, val) = store.read(&key, 1);
+    assert_eq!(res, status::OK);
+    assert_eq!(val.unwrap().unwrap(), "Hello, World!");
+}
+
+#[test]
+fn typed_delete() {
+    let store: TypedFasterKv<u64, u64> = TypedFasterKv::default();
+    let key: u64 = 1;
+
+    store.upsert(&key, &1337, 1);
+    let delete = store.delete(&key, 1);
+    assert!(delete == status::OK || delete == status::PENDING);
+
+    let (res, val) = store.read(&key, 1);
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(val.unwrap(), None);
+}
+
+#[test]
+fn typed_delegates_store_operations() {
+    let store: TypedFasterKv<u64, u64> = TypedFasterKv::default();
+    store.upsert(&1, &1337, 1);
+    assert!(store.size() > 0);
+}
+
+#[test]
+fn typed_session_numbers_operations() {
+    let store: TypedFasterKv<u64, String> = TypedFasterKv::default();
+    let session = store.start_session();
+
+    let upsert = session.upsert(&1, &String::from("Hello"));
+    assert!(upsert == status::OK || upsert == status::PENDING);
+    let (res, val) = session.read(&1);
+    assert_eq!(res, status::OK);
+    assert_eq!(val.unwrap().unwrap(), "Hello");
+
+    let rmw = session.rmw(&1, &String::from(", World!"));
+    assert!(rmw == status::OK || rmw == status::PENDING);
+    let (_, val) = session.read(&1);
+    assert_eq!(val.unwrap().unwrap(), "Hello, World!");
+
+    session.delete(&1);
+    let (res, _) = session.read(&1);
+    assert_eq!(res, status::NOT_FOUND);
+    assert_eq!(session.serial_number(), 6);
+}